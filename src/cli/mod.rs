@@ -0,0 +1,373 @@
+use ::game::{self, SudokuBoard, Difficulty, GameSource, ImportFormat, ExportFormat, PrintedPuzzle};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Printed when the command line can not be understood
+pub const USAGE : &str = "usage: sudoku [--data-dir <directory>] [--config-dir <directory>] [<command> <arguments>...]
+       sudoku --help
+
+Without a command the game window is opened, or the terminal frontend when the game is built without it. The commands are:
+  solve [<puzzle>...]                print the solution of every puzzle
+  count [--limit <n>] [<puzzle>...]  print the number of solutions of every puzzle, counting up to the limit, 1000 by default
+  rate [<puzzle>...]                 print the difficulty, score, hardest technique and number of steps of every puzzle
+  generate --difficulty <difficulty> [--seed <seed>] [--count <n>] [--source generator|database|collection]
+                                     print new puzzles, each followed by a tab and its seed, the difficulty being easy, medium, hard or very_hard
+  validate <file>...                 check database files, a puzzle per line, for puzzles that are invalid or not unique
+  print [--solution] [--svg <prefix>] [<puzzle>...]
+                                     print the puzzles as text grids, or write them to <prefix>-puzzles-N.svg pages,
+                                     with --solution followed by their solutions, on <prefix>-solutions-N.svg pages
+  import <file>...                   add the puzzles of the files to the user collection
+  tui                                play in the terminal instead of the game window, keys are read a line at a time,
+                                     so they take effect once Enter is pressed
+  help                               print this text
+
+A puzzle is either its 81 cells, or a file of puzzles in any of the formats import reads; a tab and a seed may follow each puzzle, print puts it on the page.
+With - or no puzzles at all, the puzzles are read from standard input.";
+
+/// Runs a command with its arguments, returns the exit status: 0 on success, 1 when some of the puzzles or files fail and 2 for an invalid command line
+pub fn run(command : &str, args : &[String]) -> i32 {
+    let result = match command {
+        "solve" => solve(args),
+        "count" => count(args),
+        "rate" => rate(args),
+        "generate" => generate(args),
+        "validate" => validate(args),
+        "print" => print(args),
+        "import" => import(args),
+        "tui" => if args.is_empty() {
+            ::tui::run();
+            Ok(true)
+        } else {
+            Err(format!("unexpected argument {}", args[0]))
+        },
+        "help" => {
+            println!("{}", USAGE);
+            Ok(true)
+        },
+        _ => Err(format!("unknown command {}", command))
+    };
+    match result {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            2
+        }
+    }
+}
+
+/// The options of a command, by name without the leading --, and its other arguments
+/// Flags are options without a value, they are mapped to an empty string
+type CommandLine = (HashMap<String, String>, Vec<String>);
+
+fn parse_options(args : &[String], flags : &[&str], options : &[&str]) -> Result<CommandLine, String> {
+    let mut values : HashMap<String, String> = HashMap::new();
+    let mut arguments : Vec<String> = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            arguments.push(arg.clone());
+            continue;
+        }
+        let name = &arg[2..];
+        if flags.contains(&name) {
+            values.insert(String::from(name), String::new());
+        } else if options.contains(&name) {
+            let value = args.next().ok_or_else(|| format!("{} requires a value", arg))?;
+            values.insert(String::from(name), value.clone());
+        } else {
+            return Err(format!("unknown option {}", arg));
+        }
+    }
+    Ok((values, arguments))
+}
+
+fn parse_number<T : ::std::str::FromStr>(values : &HashMap<String, String>, name : &str) -> Result<Option<T>, String> {
+    match values.get(name) {
+        Some(value) => value.parse().map(Some).map_err(|_| format!("--{} must be a number, not {}", name, value)),
+        None => Ok(None)
+    }
+}
+
+/// A puzzle given on the command line
+pub struct InputPuzzle {
+    /// Where the puzzle came from: the argument, or the file and line
+    pub label: String,
+    pub board: SudokuBoard,
+    /// The seed the puzzle was made from, when it follows the puzzle after a tab as generate prints it
+    pub seed: Option<u64>
+}
+
+/// Reads the puzzles given on the command line
+/// An argument is read as a file if there is one by that name and as a puzzle otherwise, - stands for standard input, as does giving no arguments at all
+/// Returns the puzzles that could be read and what is wrong with the others
+pub fn read_puzzles(args : &[String]) -> (Vec<InputPuzzle>, Vec<String>) {
+    let mut puzzles : Vec<InputPuzzle> = Vec::new();
+    let mut errors : Vec<String> = Vec::new();
+    let stdin_only = [String::from("-")];
+    let args = if args.is_empty() { &stdin_only[..] } else { args };
+    for arg in args.iter() {
+        let contents = if arg == "-" {
+            let mut contents = String::new();
+            match io::stdin().read_to_string(&mut contents) {
+                Ok(_) => Some(contents),
+                Err(error) => {
+                    errors.push(format!("standard input: {}", error));
+                    continue;
+                }
+            }
+        } else if Path::new(arg).is_file() {
+            let mut contents = String::new();
+            match File::open(arg).and_then(|mut file| file.read_to_string(&mut contents)) {
+                Ok(_) => Some(contents),
+                Err(error) => {
+                    errors.push(format!("{}: {}", arg, error));
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
+        match contents {
+            Some(contents) => {
+                let name = if arg == "-" { "stdin" } else { arg.as_str() };
+                let (contents, seeds) = split_seed_column(contents.as_str());
+                let parsed = game::parse_puzzles(contents.as_str(), ImportFormat::detect(Path::new(arg), contents.as_str()));
+                puzzles.extend(parsed.puzzles.into_iter().map(|(number, board)| InputPuzzle{
+                    label: format!("{}:{}", name, number),
+                    board,
+                    seed: seeds.get(&number).cloned()
+                }));
+                errors.extend(parsed.invalid.into_iter().map(|(number, error)| format!("{}:{}: {}", name, number, error)));
+            },
+            None => match arg.parse::<SudokuBoard>() {
+                Ok(board) => puzzles.push(InputPuzzle{
+                    label: arg.clone(),
+                    board,
+                    seed: None
+                }),
+                Err(error) => errors.push(format!("{}: neither a file nor a puzzle, {}", arg, error))
+            }
+        }
+    }
+    (puzzles, errors)
+}
+
+/// Takes the seeds off lines of the form "<puzzle>\t<seed>", returns the lines without them and the seeds by line number counted from one
+/// Lines whose last column is not a number are left as they are
+fn split_seed_column(contents : &str) -> (String, HashMap<usize, u64>) {
+    let mut seeds : HashMap<usize, u64> = HashMap::new();
+    let lines : Vec<&str> = contents.lines().enumerate().map(|(index, line)| {
+        if let Some(separator) = line.trim_end().rfind('\t') {
+            let is_after_puzzle = !line[..separator].trim().is_empty();
+            if let (true, Ok(seed)) = (is_after_puzzle, line[separator + 1..].trim().parse::<u64>()) {
+                seeds.insert(index + 1, seed);
+                return &line[..separator];
+            }
+        }
+        line
+    }).collect();
+    (lines.join("\n"), seeds)
+}
+
+/// Reads the puzzles and reports the ones that can not be read, returns whether all of them could be
+fn read_puzzles_reporting(args : &[String]) -> (Vec<InputPuzzle>, bool) {
+    let (puzzles, errors) = read_puzzles(args);
+    for error in errors.iter() {
+        eprintln!("{}", error);
+    }
+    (puzzles, errors.is_empty())
+}
+
+/// A line per puzzle, "no solution" for puzzles that have none
+fn solve(args : &[String]) -> Result<bool, String> {
+    let (_, arguments) = parse_options(args, &[], &[])?;
+    let (puzzles, mut success) = read_puzzles_reporting(&arguments);
+    for puzzle in puzzles.iter() {
+        match game::solve(&puzzle.board) {
+            Some(solution) => println!("{}", solution),
+            None => {
+                println!("no solution");
+                success = false;
+            }
+        }
+    }
+    Ok(success)
+}
+
+fn count(args : &[String]) -> Result<bool, String> {
+    let (values, arguments) = parse_options(args, &[], &["limit"])?;
+    let limit = parse_number::<usize>(&values, "limit")?.unwrap_or(1000);
+    let (puzzles, success) = read_puzzles_reporting(&arguments);
+    for puzzle in puzzles.iter() {
+        println!("{}", game::count_solutions(&puzzle.board, limit));
+    }
+    Ok(success)
+}
+
+/// A tab-separated line per puzzle: difficulty, score, hardest technique and number of steps
+/// Puzzles without a unique solution can not be rated
+fn rate(args : &[String]) -> Result<bool, String> {
+    let (_, arguments) = parse_options(args, &[], &[])?;
+    let (puzzles, mut success) = read_puzzles_reporting(&arguments);
+    for puzzle in puzzles.iter() {
+        match game::rate(&puzzle.board) {
+            Some(rating) => println!("{}\t{:.1}\t{}\t{}", rating.difficulty.key(), rating.score,
+                rating.hardest_technique.map_or("-", |technique| technique.name()), rating.steps),
+            None => {
+                println!("no unique solution");
+                success = false;
+            }
+        }
+    }
+    Ok(success)
+}
+
+/// Prints a puzzle per line, followed by a tab and its seed; consecutive seeds starting from the given one produce the puzzles after the first
+fn generate(args : &[String]) -> Result<bool, String> {
+    let (values, arguments) = parse_options(args, &[], &["difficulty", "seed", "count", "source"])?;
+    if let Some(argument) = arguments.first() {
+        return Err(format!("unexpected argument {}", argument));
+    }
+    let difficulty = match values.get("difficulty") {
+        Some(key) => Difficulty::from_key(key).ok_or_else(|| format!("unknown difficulty {}", key))?,
+        None => return Err(String::from("generate requires --difficulty"))
+    };
+    let source = match values.get("source").map(|source| source.as_str()) {
+        None | Some("generator") => GameSource::Generator,
+        Some("database") => GameSource::Database,
+        Some("collection") => GameSource::Collection,
+        Some(source) => return Err(format!("unknown source {}", source))
+    };
+    let seed = parse_number::<u64>(&values, "seed")?;
+    let count = parse_number::<u64>(&values, "count")?.unwrap_or(1);
+    // the predefined games are only read when they are asked for
+    let database = if source == GameSource::Database {
+        match game::Database::load() {
+            Ok((database, skipped_lines)) => {
+                for skipped_line in skipped_lines.iter() {
+                    eprintln!("{}", skipped_line);
+                }
+                Some(database)
+            },
+            Err(error) => {
+                eprintln!("{}", error);
+                return Ok(false);
+            }
+        }
+    } else {
+        None
+    };
+    for index in 0..count {
+        let seed = match seed {
+            Some(seed) => seed.wrapping_add(index),
+            None => game::random_seed()
+        };
+        let (board, _) = game::generate_game(difficulty, source, seed, database.as_ref()).expect("the database is loaded for predefined games");
+        println!("{}\t{}", board, seed);
+    }
+    Ok(true)
+}
+
+/// Reports the lines of database files that are not puzzles or do not have a unique solution
+/// Puzzles in a file named after a difficulty that are rated otherwise are pointed out, but do not make the file invalid
+fn validate(args : &[String]) -> Result<bool, String> {
+    let (_, arguments) = parse_options(args, &[], &[])?;
+    if arguments.is_empty() {
+        return Err(String::from("validate requires a file"));
+    }
+    let mut success = true;
+    for path in arguments.iter() {
+        let mut contents = String::new();
+        if let Err(error) = File::open(path).and_then(|mut file| file.read_to_string(&mut contents)) {
+            eprintln!("{}: {}", path, error);
+            success = false;
+            continue;
+        }
+        let file_difficulty = Path::new(path).file_stem().and_then(|stem| stem.to_str()).and_then(Difficulty::from_key);
+        let parsed = game::parse_puzzles(contents.as_str(), ImportFormat::Sdm);
+        let puzzles = parsed.puzzles;
+        let total = puzzles.len() + parsed.invalid.len();
+        let mut problems : Vec<(usize, String)> = parsed.invalid.into_iter().map(|(line, error)| (line, error.to_string())).collect();
+        for (line, board) in puzzles.iter() {
+            match game::rate(board) {
+                Some(rating) => if file_difficulty.is_some_and(|difficulty| difficulty != rating.difficulty) {
+                    println!("{}:{}: note: rated {}", path, line, rating.difficulty.key());
+                },
+                None => problems.push((*line, String::from("the puzzle does not have a unique solution")))
+            }
+        }
+        problems.sort();
+        for (line, problem) in problems.iter() {
+            println!("{}:{}: {}", path, line, problem);
+        }
+        println!("{}: {} puzzles, {} invalid", path, total, problems.len());
+        success &= problems.is_empty();
+    }
+    Ok(success)
+}
+
+fn print(args : &[String]) -> Result<bool, String> {
+    let (values, arguments) = parse_options(args, &["solution"], &["svg"])?;
+    let include_solution = values.contains_key("solution");
+    let (puzzles, success) = read_puzzles_reporting(&arguments);
+    match values.get("svg") {
+        Some(prefix) => {
+            let printed_puzzles : Vec<PrintedPuzzle> = puzzles.into_iter().map(|puzzle| PrintedPuzzle{
+                title: puzzle.label,
+                seed: puzzle.seed,
+                board: puzzle.board
+            }).collect();
+            let mut pages : Vec<(String, String)> = game::puzzle_pages_svg(&printed_puzzles).into_iter().enumerate()
+                .map(|(index, page)| (format!("{}-puzzles-{}.svg", prefix, index + 1), page)).collect();
+            if include_solution {
+                pages.extend(game::solution_pages_svg(&printed_puzzles).into_iter().enumerate()
+                    .map(|(index, page)| (format!("{}-solutions-{}.svg", prefix, index + 1), page)));
+            }
+            for (path, page) in pages.iter() {
+                if let Err(error) = fs::write(path, page.as_bytes()) {
+                    eprintln!("{}: {}", path, error);
+                    return Ok(false);
+                }
+                println!("{}", path);
+            }
+        },
+        None => {
+            let grids : Vec<String> = puzzles.iter()
+                .map(|puzzle| game::export_game(ExportFormat::Ascii, puzzle.label.as_str(), &puzzle.board, None, include_solution))
+                .collect();
+            print!("{}", grids.join("\n"));
+        }
+    }
+    Ok(success)
+}
+
+fn import(args : &[String]) -> Result<bool, String> {
+    let (_, arguments) = parse_options(args, &[], &[])?;
+    if arguments.is_empty() {
+        return Err(String::from("import requires a file"));
+    }
+    let mut success = true;
+    for path in arguments.iter() {
+        match game::import_file(Path::new(path)) {
+            Ok(report) => {
+                println!("{}: {} puzzles added ({} easy, {} medium, {} hard, {} very hard), {} already in the collection",
+                    path, report.added_total(), report.added[0], report.added[1], report.added[2], report.added[3], report.duplicates);
+                for (number, rejection) in report.rejected.iter() {
+                    println!("{}: puzzle {} skipped: {}", path, number, rejection);
+                }
+            },
+            Err(error) => {
+                eprintln!("{}: {}", path, error);
+                success = false;
+            }
+        }
+    }
+    Ok(success)
+}
+
+// Declares the test module
+#[cfg(test)]
+mod tests;
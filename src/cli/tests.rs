@@ -0,0 +1,40 @@
+use super::{parse_options, read_puzzles};
+use ::game::SudokuBoard;
+use std::env::temp_dir;
+use std::fs;
+
+const PUZZLE : &str = "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+fn strings(args : &[&str]) -> Vec<String> {
+    args.iter().map(|arg| String::from(*arg)).collect()
+}
+
+#[test]
+fn parse_options_separates_arguments() {
+    let (values, arguments) = parse_options(&strings(&["--limit", "5", "a", "--solution", "b"]), &["solution"], &["limit"])
+        .expect("cli::parse_options() refuses known options");
+    assert_eq!(values.get("limit").map(|value| value.as_str()), Some("5"), "cli::parse_options() does not take the value of an option");
+    assert_eq!(values.get("solution").map(|value| value.as_str()), Some(""), "cli::parse_options() does not record flags");
+    assert_eq!(arguments, strings(&["a", "b"]), "cli::parse_options() does not keep the other arguments in order");
+    assert!(parse_options(&strings(&["--limit"]), &[], &["limit"]).is_err(), "cli::parse_options() accepts an option without its value");
+    assert!(parse_options(&strings(&["--unknown"]), &[], &["limit"]).is_err(), "cli::parse_options() accepts unknown options");
+}
+
+#[test]
+fn read_puzzles_from_arguments_and_files() {
+    let mut path = temp_dir();
+    path.push(format!("sudoku_cli_read_puzzles_{}.sdm", ::std::process::id()));
+    fs::write(&path, format!("{}\n12345\n{}\t42\n", PUZZLE, PUZZLE.replace('0', "."))).unwrap();
+    let path_string = String::from(path.to_str().unwrap());
+
+    let (puzzles, errors) = read_puzzles(&[String::from(PUZZLE), path_string.clone(), String::from("not-a-puzzle")]);
+    let _ = fs::remove_file(&path);
+    let board = SudokuBoard::new(PUZZLE);
+    assert_eq!(puzzles.len(), 3, "cli::read_puzzles() does not read puzzles from both arguments and files");
+    assert!(puzzles.iter().all(|puzzle| puzzle.board == board), "cli::read_puzzles() misreads puzzles");
+    assert_eq!(puzzles[2].label, format!("{}:3", path_string), "cli::read_puzzles() does not label puzzles with their file and line");
+    assert_eq!(puzzles[2].seed, Some(42), "cli::read_puzzles() does not read the seed after a puzzle");
+    assert_eq!(puzzles[1].seed, None, "cli::read_puzzles() makes up a seed for a puzzle without one");
+    assert_eq!(errors.len(), 2, "cli::read_puzzles() does not report invalid lines and arguments");
+    assert!(errors[0].starts_with(format!("{}:2: ", path_string).as_str()), "cli::read_puzzles() does not report the line of an invalid puzzle");
+}
@@ -0,0 +1,27 @@
+use std::time::{Duration, SystemTime};
+
+/// Formats a duration as minutes and seconds, or hours, minutes and seconds once it reaches an hour
+pub fn format_duration(duration : Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, (seconds / 60) % 60, seconds % 60)
+    } else {
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+/// Describes how long ago a moment was in rough terms, such as "5 minutes ago"
+pub fn format_time_ago(time : SystemTime) -> String {
+    let seconds = SystemTime::now().duration_since(time).map(|duration| duration.as_secs()).unwrap_or(0);
+    if seconds < 60 {
+        return String::from("just now");
+    }
+    let (amount, unit) = if seconds < 3600 {
+        (seconds / 60, "minute")
+    } else if seconds < 24 * 3600 {
+        (seconds / 3600, "hour")
+    } else {
+        (seconds / (24 * 3600), "day")
+    };
+    format!("{} {}{} ago", amount, unit, if amount == 1 { "" } else { "s" })
+}
@@ -0,0 +1,140 @@
+use super::super::{SudokuBoard, ParseBoardError};
+use std::path::Path;
+
+/// The puzzle file formats that can be imported
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ImportFormat {
+    /// .sdm, a puzzle per line, the format of resources/games/*.sud as well
+    Sdm,
+    /// .sdk, a single puzzle as a 9x9 grid of lines, with # comment lines and an optional [Puzzle] header
+    Sdk,
+    /// .ss, a single SadMan Simple Sudoku grid, boxes separated by | and ---
+    Ss,
+    /// The XML format of OpenSudoku, a <game data="..."/> element per puzzle
+    OpenSudoku
+}
+
+impl ImportFormat {
+    /// Picks the format by the file extension, for other extensions it is guessed from the contents
+    pub fn detect(path : &Path, contents : &str) -> ImportFormat {
+        let extension = path.extension().and_then(|extension| extension.to_str()).map(|extension| extension.to_lowercase());
+        match extension.as_deref() {
+            Some("sdm") | Some("sud") => ImportFormat::Sdm,
+            Some("sdk") => ImportFormat::Sdk,
+            Some("ss") => ImportFormat::Ss,
+            Some("opensudoku") | Some("xml") => ImportFormat::OpenSudoku,
+            _ => if contents.trim_start().starts_with('<') {
+                ImportFormat::OpenSudoku
+            } else if contents.lines().any(|line| line.contains('|')) {
+                ImportFormat::Ss
+            } else if contents.lines().filter(|line| !line.trim().is_empty()).all(|line| line.trim().len() >= 81) {
+                ImportFormat::Sdm
+            } else {
+                ImportFormat::Sdk
+            }
+        }
+    }
+}
+
+/// The puzzles read from a file, along with their line numbers, or for OpenSudoku files their game numbers, counted from one
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ParsedPuzzles {
+    pub puzzles: Vec<(usize, SudokuBoard)>,
+    /// The puzzles that can not be read, with the same numbering
+    pub invalid: Vec<(usize, ParseBoardError)>
+}
+
+/// Reads the puzzles of a file in the given format
+pub fn parse_puzzles(contents : &str, format : ImportFormat) -> ParsedPuzzles {
+    let mut parsed = ParsedPuzzles::default();
+    let mut add = |number : usize, puzzle : Result<SudokuBoard, ParseBoardError>| {
+        match puzzle {
+            Ok(board) => parsed.puzzles.push((number, board)),
+            Err(error) => parsed.invalid.push((number, error))
+        }
+    };
+    match format {
+        ImportFormat::Sdm => {
+            for (index, line) in contents.lines().enumerate() {
+                if !line.trim().is_empty() {
+                    add(index + 1, line.parse());
+                }
+            }
+        },
+        ImportFormat::Sdk | ImportFormat::Ss => {
+            if let Some(grid) = single_grid(contents) {
+                add(1, grid.parse());
+            }
+        },
+        ImportFormat::OpenSudoku => {
+            for (index, data) in open_sudoku_game_data(contents).into_iter().enumerate() {
+                add(index + 1, data.parse());
+            }
+        }
+    }
+    parsed
+}
+
+/// The lines of a file holding a single grid, without # comments and section headers
+/// Only the first section counts, .sdk files may be followed by a [State] section with the player's progress
+fn single_grid(contents : &str) -> Option<String> {
+    let mut grid = String::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.starts_with('#') || line.starts_with('*') {
+            continue;
+        }
+        if line.starts_with('[') {
+            if grid.is_empty() {
+                continue;
+            } else {
+                break;
+            }
+        }
+        grid.push_str(line);
+        grid.push('\n');
+    }
+    if grid.trim().is_empty() {
+        None
+    } else {
+        Some(grid)
+    }
+}
+
+/// The data attributes of the <game> elements of an OpenSudoku file
+/// This is not a general XML parser, but OpenSudoku files only ever have the attribute on the element itself
+fn open_sudoku_game_data(contents : &str) -> Vec<String> {
+    let mut data : Vec<String> = Vec::new();
+    let mut rest = contents;
+    while let Some(start) = rest.find("<game") {
+        rest = &rest[start + "<game".len()..];
+        // <games> and the like are other elements
+        if !rest.starts_with(|ch : char| ch.is_whitespace() || ch == '/' || ch == '>') {
+            continue;
+        }
+        let element = &rest[..rest.find('>').unwrap_or(rest.len())];
+        if let Some(value) = attribute_value(element, "data") {
+            data.push(value);
+        }
+    }
+    data
+}
+
+fn attribute_value(element : &str, name : &str) -> Option<String> {
+    let mut rest = element;
+    while let Some(start) = rest.find(name) {
+        let preceded_by_space = rest[..start].ends_with(|ch : char| ch.is_whitespace());
+        rest = rest[start + name.len()..].trim_start();
+        if !preceded_by_space || !rest.starts_with('=') {
+            continue;
+        }
+        rest = rest[1..].trim_start();
+        let quote = rest.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let value = &rest[1..];
+        return value.find(quote).map(|end| String::from(&value[..end]));
+    }
+    None
+}
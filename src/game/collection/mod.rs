@@ -0,0 +1,151 @@
+// Declare the submodules
+mod import;
+
+use super::{SudokuBoard, ParseBoardError, Difficulty, rate};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+pub use self::import::{ImportFormat, ParsedPuzzles, parse_puzzles};
+
+/// The user collection is kept in this directory within the data directory, a .sdm file per difficulty
+const DIRECTORY_COLLECTION : &str = "collection";
+/// File names of the difficulties' puzzles, in the order of Difficulty::ALL
+const FILENAMES_COLLECTION : [&str; 4] = ["easy.sdm", "medium.sdm", "hard.sdm", "very_hard.sdm"];
+
+/// Why a puzzle of an imported file was left out of the collection
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RejectedPuzzle {
+    Invalid(ParseBoardError),
+    /// The puzzle has no solution or more than one
+    NotUnique
+}
+
+impl fmt::Display for RejectedPuzzle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RejectedPuzzle::Invalid(error) => write!(f, "{}", error),
+            RejectedPuzzle::NotUnique => write!(f, "the puzzle does not have a unique solution")
+        }
+    }
+}
+
+/// What importing a file has done
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ImportReport {
+    pub format: ImportFormat,
+    /// Number of puzzles added to the collection, per difficulty in the order of Difficulty::ALL
+    pub added: [usize; 4],
+    /// Puzzles that were already in the collection
+    pub duplicates: usize,
+    /// The puzzles left out, numbered by line, or by game for OpenSudoku files, counted from one
+    pub rejected: Vec<(usize, RejectedPuzzle)>
+}
+
+/// What adding puzzles to the user collection has done
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CollectionUpdate {
+    /// Number of puzzles added, per difficulty in the order of Difficulty::ALL
+    pub added: [usize; 4],
+    /// Puzzles that were already in the collection
+    pub duplicates: usize,
+    /// The numbers of the puzzles left out for not having a unique solution
+    pub not_unique: Vec<usize>
+}
+
+impl ImportReport {
+    pub fn added_total(&self) -> usize {
+        self.added.iter().sum()
+    }
+}
+
+fn collection_path(difficulty : Difficulty) -> PathBuf {
+    let mut path_buf = ::util::data_dir();
+    path_buf.push(DIRECTORY_COLLECTION);
+    path_buf.push(FILENAMES_COLLECTION[difficulty as usize]);
+    path_buf
+}
+
+/// The puzzles of the given difficulty in the user collection, as 81-digit strings
+/// Lines that are not valid puzzles are skipped
+pub fn collection_games(difficulty : Difficulty) -> Vec<String> {
+    let mut games : Vec<String> = Vec::new();
+    if let Ok(file) = File::open(collection_path(difficulty)) {
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            if let Ok(board) = line.parse::<SudokuBoard>() {
+                games.push(board.to_string());
+            }
+        }
+    }
+    games
+}
+
+/// Sorts numbered puzzles into the user collection by their rated difficulty, leaving out the ones already in it and the ones without a unique solution
+pub fn add_to_collection(puzzles : &[(usize, SudokuBoard)]) -> io::Result<CollectionUpdate> {
+    use std::io::Write;
+    let mut known : HashSet<String> = HashSet::new();
+    for difficulty in Difficulty::ALL.iter() {
+        known.extend(collection_games(*difficulty));
+    }
+    let mut new_games : [Vec<String>; 4] = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+    let mut duplicates = 0;
+    let mut not_unique : Vec<usize> = Vec::new();
+    for (number, puzzle) in puzzles.iter() {
+        let game = puzzle.to_string();
+        if known.contains(&game) {
+            duplicates += 1;
+            continue;
+        }
+        match rate(puzzle) {
+            Some(rating) => {
+                known.insert(game.clone());
+                new_games[rating.difficulty as usize].push(game);
+            },
+            None => not_unique.push(*number)
+        }
+    }
+    let mut added = [0usize; 4];
+    for difficulty in Difficulty::ALL.iter() {
+        let games = &new_games[*difficulty as usize];
+        if games.is_empty() {
+            continue;
+        }
+        let path = collection_path(*difficulty);
+        fs::create_dir_all(path.parent().unwrap())?;
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+        let mut lines = games.join("\n");
+        lines.push('\n');
+        file.write_all(lines.as_bytes())?;
+        added[*difficulty as usize] = games.len();
+    }
+    Ok(CollectionUpdate{
+        added,
+        duplicates,
+        not_unique
+    })
+}
+
+/// Imports the puzzles of a .sdm, .sdk, .ss or OpenSudoku file into the user collection
+pub fn import_file(path : &Path) -> io::Result<ImportReport> {
+    use std::io::Read;
+    let mut contents = String::new();
+    File::open(path)?.read_to_string(&mut contents)?;
+    let format = ImportFormat::detect(path, contents.as_str());
+    let parsed = parse_puzzles(contents.as_str(), format);
+    let update = add_to_collection(&parsed.puzzles)?;
+    let mut rejected : Vec<(usize, RejectedPuzzle)> = parsed.invalid.into_iter().map(|(number, error)| (number, RejectedPuzzle::Invalid(error))).collect();
+    rejected.extend(update.not_unique.into_iter().map(|number| (number, RejectedPuzzle::NotUnique)));
+    rejected.sort_by_key(|&(number, _)| number);
+    Ok(ImportReport{
+        format,
+        added: update.added,
+        duplicates: update.duplicates,
+        rejected
+    })
+}
+
+// Declares the test module
+#[cfg(test)]
+mod tests;
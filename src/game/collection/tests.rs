@@ -0,0 +1,51 @@
+use super::{ImportFormat, ParsedPuzzles, parse_puzzles};
+use super::super::{SudokuBoard, ParseBoardError};
+use std::path::Path;
+
+const PUZZLE : &str = "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+fn puzzle() -> SudokuBoard {
+    SudokuBoard::new(PUZZLE)
+}
+
+#[test]
+fn import_detect_format() {
+    assert_eq!(ImportFormat::detect(Path::new("puzzles.SDM"), ""), ImportFormat::Sdm, "game::ImportFormat::detect() does not go by the extension");
+    assert_eq!(ImportFormat::detect(Path::new("puzzle.sdk"), ""), ImportFormat::Sdk, "game::ImportFormat::detect() does not go by the extension");
+    assert_eq!(ImportFormat::detect(Path::new("puzzle.ss"), ""), ImportFormat::Ss, "game::ImportFormat::detect() does not go by the extension");
+    assert_eq!(ImportFormat::detect(Path::new("easy.opensudoku"), ""), ImportFormat::OpenSudoku, "game::ImportFormat::detect() does not go by the extension");
+    assert_eq!(ImportFormat::detect(Path::new("download"), "<?xml version=\"1.0\"?>"), ImportFormat::OpenSudoku, "game::ImportFormat::detect() does not recognize XML");
+    assert_eq!(ImportFormat::detect(Path::new("download"), PUZZLE), ImportFormat::Sdm, "game::ImportFormat::detect() does not recognize puzzle lines");
+}
+
+#[test]
+fn import_sdm() {
+    let contents = format!("{}\n\n{}\n123\n", PUZZLE, PUZZLE.replace('0', "."));
+    let ParsedPuzzles{puzzles, invalid} = parse_puzzles(contents.as_str(), ImportFormat::Sdm);
+    assert_eq!(puzzles, vec![(1, puzzle()), (3, puzzle())], "game::parse_puzzles() does not read .sdm lines");
+    assert_eq!(invalid, vec![(4, ParseBoardError::WrongLength(3))], "game::parse_puzzles() does not report invalid .sdm lines");
+}
+
+#[test]
+fn import_sdk() {
+    let contents = "#ASomeone\n#DA classic\n[Puzzle]\n53..7....\n6..195...\n.98....6.\n8...6...3\n4..8.3..1\n7...2...6\n.6....28.\n...419..5\n....8..79\n[State]\n534678912\n";
+    let ParsedPuzzles{puzzles, invalid} = parse_puzzles(contents, ImportFormat::Sdk);
+    assert_eq!(puzzles, vec![(1, puzzle())], "game::parse_puzzles() does not read .sdk grids");
+    assert!(invalid.is_empty(), "game::parse_puzzles() reports a valid .sdk grid as invalid");
+}
+
+#[test]
+fn import_ss() {
+    let contents = "53.|.7.|...\n6..|195|...\n.98|...|.6.\n-----------\n8..|.6.|..3\n4..|8.3|..1\n7..|.2.|..6\n-----------\n.6.|...|28.\n...|419|..5\n...|.8.|.79\n";
+    let puzzles = parse_puzzles(contents, ImportFormat::Ss).puzzles;
+    assert_eq!(puzzles, vec![(1, puzzle())], "game::parse_puzzles() does not read .ss grids");
+}
+
+#[test]
+fn import_open_sudoku() {
+    let contents = format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opensudoku>\n<name>Easy</name>\n<games>\n<game data=\"{}\"/>\n<game level='2' data='{}' />\n<game data=\"12\"/>\n</games>\n</opensudoku>\n",
+        PUZZLE, PUZZLE);
+    let ParsedPuzzles{puzzles, invalid} = parse_puzzles(contents.as_str(), ImportFormat::OpenSudoku);
+    assert_eq!(puzzles, vec![(1, puzzle()), (2, puzzle())], "game::parse_puzzles() does not read OpenSudoku games");
+    assert_eq!(invalid, vec![(3, ParseBoardError::WrongLength(2))], "game::parse_puzzles() does not report invalid OpenSudoku games");
+}
@@ -0,0 +1,145 @@
+use super::super::{SudokuBoard, solve, has_unique_solution, rate};
+use super::Difficulty;
+use super::rand::Rng;
+
+/// Number of givens the generator aims for; zero means remove as many as uniqueness allows
+/// Below 27 givens more than two thirds of the board is empty, which makes a puzzle of singles at least medium, see rate()
+fn target_givens(difficulty : Difficulty) -> usize {
+    match difficulty {
+        Difficulty::Easy => 35,
+        Difficulty::Medium => 26,
+        Difficulty::Hard => 25,
+        Difficulty::VeryHard => 0
+    }
+}
+
+/// The most givens a generated puzzle may keep, very hard puzzles keep no more than hard ones
+fn max_givens(difficulty : Difficulty) -> usize {
+    match target_givens(difficulty) {
+        0 => target_givens(Difficulty::Hard),
+        target => target
+    }
+}
+
+/// How many full boards are tried before settling for the puzzle rated closest to the requested difficulty
+/// About half of the candidates are rated at the difficulty they were made for, so settling hardly ever happens
+const GENERATION_ATTEMPTS : usize = 20;
+
+/// Generates a new puzzle with a unique solution for the given difficulty
+/// The number of givens only gets a candidate close, it is kept once it has few enough givens and rate() agrees with the difficulty;
+/// failing that, the candidate rated closest to the difficulty, then with the fewest givens, is returned
+pub fn generate_board<R : Rng>(difficulty : Difficulty, prng : &mut R) -> SudokuBoard {
+    let target = target_givens(difficulty);
+    let mut closest : Option<(SudokuBoard, (i32, usize))> = None;
+    for _ in 0..GENERATION_ATTEMPTS {
+        let (board, givens) = remove_givens(random_solution(prng), target, prng);
+        let rated = rate(&board).expect("the generator only leaves puzzles with a unique solution").difficulty;
+        let distance = (rated as i32 - difficulty as i32).abs();
+        if distance == 0 && givens <= max_givens(difficulty) {
+            return board;
+        }
+        let is_closer = match closest {
+            Some((_, closest_by)) => (distance, givens) < closest_by,
+            None => true
+        };
+        if is_closer {
+            closest = Some((board, (distance, givens)));
+        }
+    }
+    closest.unwrap().0
+}
+
+/// Builds a random completely filled board
+fn random_solution<R : Rng>(prng : &mut R) -> SudokuBoard {
+    // the diagonal quadrants share no rows or columns, so filling them with independent permutations never conflicts
+    let mut board = SudokuBoard([[0u8; 9]; 9]);
+    for q in 0..3 {
+        let mut digits = [1u8, 2, 3, 4, 5, 6, 7, 8, 9];
+        prng.shuffle(&mut digits);
+        for (k, digit) in digits.iter().enumerate() {
+            board[3 * q + k / 3][3 * q + k % 3] = *digit;
+        }
+    }
+    let board = solve(&board).expect("a board with only the diagonal quadrants filled is always solvable");
+
+    // the solver completes the rest deterministically, so the lines are shuffled for more variety
+    shuffle_lines(&board, prng)
+}
+
+/// Permutes rows within bands, bands themselves, and the same for columns, optionally transposing - all of which keep a board valid
+fn shuffle_lines<R : Rng>(board : &SudokuBoard, prng : &mut R) -> SudokuBoard {
+    let rows = random_line_order(prng);
+    let cols = random_line_order(prng);
+    let transpose : bool = prng.gen();
+    let mut result = SudokuBoard([[0u8; 9]; 9]);
+    for i in 0..9 {
+        for j in 0..9 {
+            result[i][j] = if transpose {
+                board[cols[j]][rows[i]]
+            } else {
+                board[rows[i]][cols[j]]
+            };
+        }
+    }
+    result
+}
+
+fn random_line_order<R : Rng>(prng : &mut R) -> [usize; 9] {
+    let mut bands = [0usize, 1, 2];
+    prng.shuffle(&mut bands);
+    let mut order = [0usize; 9];
+    for (position, band) in bands.iter().enumerate() {
+        let mut lines = [0usize, 1, 2];
+        prng.shuffle(&mut lines);
+        for (k, line) in lines.iter().enumerate() {
+            order[3 * position + k] = 3 * band + line;
+        }
+    }
+    order
+}
+
+/// Removes givens while the puzzle keeps a unique solution, until the target is reached
+/// Rotationally symmetric pairs go first as they make nicer looking puzzles, then single cells
+/// Returns the puzzle and its number of givens
+fn remove_givens<R : Rng>(solution : SudokuBoard, target : usize, prng : &mut R) -> (SudokuBoard, usize) {
+    let mut board = solution;
+    let mut givens = 81;
+
+    let mut pairs : Vec<usize> = (0..41).collect();
+    prng.shuffle(&mut pairs);
+    for cell in pairs.into_iter() {
+        if givens <= target {
+            break;
+        }
+        let mirror = 80 - cell;
+        let (i, j) = (cell / 9, cell % 9);
+        let (mirror_i, mirror_j) = (mirror / 9, mirror % 9);
+        board[i][j] = 0;
+        board[mirror_i][mirror_j] = 0;
+        if has_unique_solution(&board) {
+            givens -= if cell == mirror { 1 } else { 2 };
+        } else {
+            board[i][j] = solution[i][j];
+            board[mirror_i][mirror_j] = solution[mirror_i][mirror_j];
+        }
+    }
+
+    let mut cells : Vec<usize> = (0..81).collect();
+    prng.shuffle(&mut cells);
+    for cell in cells.into_iter() {
+        if givens <= target {
+            break;
+        }
+        let (i, j) = (cell / 9, cell % 9);
+        if board[i][j] != 0 {
+            board[i][j] = 0;
+            if has_unique_solution(&board) {
+                givens -= 1;
+            } else {
+                board[i][j] = solution[i][j];
+            }
+        }
+    }
+
+    (board, givens)
+}
@@ -0,0 +1,228 @@
+extern crate rand;
+
+// Declare the submodules
+mod generator;
+
+use super::{SudokuBoard, ParseBoardError};
+use self::rand::{Rng, SeedableRng};
+use self::rand::prng::ChaChaRng;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::PathBuf;
+
+/// Declared from the easiest to the hardest
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    VeryHard
+}
+
+impl Difficulty {
+    /// All the difficulties, from the easiest to the hardest
+    pub const ALL : [Difficulty; 4] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::VeryHard];
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::VeryHard => "Very hard"
+        }
+    }
+
+    /// How the difficulty is written in files and on the command line
+    pub fn key(&self) -> &'static str {
+        match *self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::VeryHard => "very_hard"
+        }
+    }
+
+    /// The difficulty written as key() returns it
+    pub fn from_key(key : &str) -> Option<Difficulty> {
+        Difficulty::ALL.iter().find(|difficulty| difficulty.key() == key).cloned()
+    }
+}
+
+/// Where new games come from
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GameSource {
+    /// A freshly generated puzzle
+    Generator,
+    /// A predefined puzzle from resources/games/*.sud
+    Database,
+    /// A puzzle the player has imported into the user collection
+    Collection
+}
+
+/// The predefined games, in the canonical form of 81 digits, for each difficulty
+pub struct Database {
+    easy: Vec<String>,
+    medium: Vec<String>,
+    hard: Vec<String>,
+    very_hard: Vec<String>
+}
+
+/// Why the game database could not be loaded, each naming the file at fault
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DatabaseError {
+    /// The file is not on the resource search path
+    NotFound(String),
+    /// The file exists but could not be read
+    Unreadable(String),
+    /// None of the lines of the file is a valid game
+    NoValidGames(String)
+}
+
+impl fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DatabaseError::NotFound(file_name) => write!(f, "the game database file {} is not found", file_name),
+            DatabaseError::Unreadable(file_name) => write!(f, "the game database file {} can not be read", file_name),
+            DatabaseError::NoValidGames(file_name) => write!(f, "the game database file {} has no valid games", file_name)
+        }
+    }
+}
+
+/// A line of a game database file that is not a valid game, the game is left out of the database
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SkippedLine {
+    pub path: PathBuf,
+    /// Counted from one
+    pub line_number: usize,
+    pub error: ParseBoardError
+}
+
+impl fmt::Display for SkippedLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}, line {}: {}, the game is skipped", self.path.display(), self.line_number, self.error)
+    }
+}
+
+impl Database {
+    /// Reads the game database from the files easy.sud, medium.sud, hard.sud and very_hard.sud in resources/games,
+    /// found on the resource search path of util::find_resource
+    /// Invalid lines in the files are skipped, they are returned along with the database for the frontend to report
+    pub fn load() -> Result<(Database, Vec<SkippedLine>), DatabaseError> {
+        let mut skipped_lines : Vec<SkippedLine> = Vec::new();
+        let mut read_games_file = |file_name : &str| -> Result<Vec<String>, DatabaseError> {
+            let path = ::util::find_resource(format!("games/{}", file_name).as_str()).ok_or_else(|| DatabaseError::NotFound(String::from(file_name)))?;
+            let (games, invalid_lines) = File::open(path.as_path())
+                .and_then(|file| read_database_sud_file(BufReader::new(file)))
+                .map_err(|_| DatabaseError::Unreadable(String::from(file_name)))?;
+            skipped_lines.extend(invalid_lines.into_iter().map(|(line_number, error)| SkippedLine{
+                path: path.clone(),
+                line_number,
+                error
+            }));
+            if games.is_empty() {
+                return Err(DatabaseError::NoValidGames(String::from(file_name)));
+            }
+            Ok(games)
+        };
+        let database = Database{
+            easy: read_games_file("easy.sud")?,
+            medium: read_games_file("medium.sud")?,
+            hard: read_games_file("hard.sud")?,
+            very_hard: read_games_file("very_hard.sud")?
+        };
+        Ok((database, skipped_lines))
+    }
+
+    fn games(&self, difficulty : Difficulty) -> &Vec<String> {
+        match difficulty {
+            Difficulty::Easy => &self.easy,
+            Difficulty::Medium => &self.medium,
+            Difficulty::Hard => &self.hard,
+            Difficulty::VeryHard => &self.very_hard
+        }
+    }
+}
+
+/// The valid games of a .sud file and its invalid lines
+type SudFileContents = (Vec<String>, Vec<(usize, ParseBoardError)>);
+
+/// Reads a single .sud game file, a puzzle per line
+/// Invalid lines are skipped, they are returned along with their line numbers, counted from one, and what is wrong with them
+fn read_database_sud_file<R : BufRead>(reader : R) -> io::Result<SudFileContents> {
+    let mut games : Vec<String> = Vec::new();
+    let mut invalid_lines : Vec<(usize, ParseBoardError)> = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line_string = line?;
+        if line_string.trim().is_empty() {
+            continue;
+        }
+        match line_string.parse::<SudokuBoard>() {
+            // games are kept in the canonical form of 81 digits
+            Ok(board) => games.push(board.to_string()),
+            Err(error) => invalid_lines.push((index + 1, error))
+        }
+    }
+    Ok((games, invalid_lines))
+}
+
+/// Creates a new game of the given difficulty, either by generating it or by randomly picking a predefined or an imported one
+/// When the user collection has no games of the difficulty, one is generated instead
+/// Predefined games are picked from the given database, which only has to be loaded for that source
+/// The same difficulty, source and seed always produce the same game, as long as the user collection does not change
+/// Returns the board and, for predefined and imported games, the index of the game in its database or collection;
+/// None if predefined games are asked for without a database
+pub fn generate_game(difficulty : Difficulty, source : GameSource, seed : u64, database : Option<&Database>) -> Option<(SudokuBoard, Option<usize>)> {
+    let mut prng = seeded_prng(seed);
+    let game = match source {
+        GameSource::Generator => (generator::generate_board(difficulty, &mut prng), None),
+        GameSource::Database => {
+            let (board, index) = pick_game(database?.games(difficulty), &mut prng);
+            (board, Some(index))
+        },
+        GameSource::Collection => {
+            let games = super::collection_games(difficulty);
+            if games.is_empty() {
+                (generator::generate_board(difficulty, &mut prng), None)
+            } else {
+                let (board, index) = pick_game(&games, &mut prng);
+                (board, Some(index))
+            }
+        }
+    };
+    Some(game)
+}
+
+/// Names a game created by generate_game after its difficulty, where it came from and its seed
+pub fn game_title(difficulty : Difficulty, source : GameSource, game_index : Option<usize>, seed : u64) -> String {
+    match (game_index, source) {
+        (Some(game_index), GameSource::Collection) => format!("{} collection game #{}, seed {}", difficulty.name(), game_index + 1, seed),
+        (Some(game_index), _) => format!("{} game #{}, seed {}", difficulty.name(), game_index + 1, seed),
+        (None, _) => format!("{} generated game, seed {}", difficulty.name(), seed)
+    }
+}
+
+/// Picks a seed for a new game when the player has not provided one
+pub fn random_seed() -> u64 {
+    rand::thread_rng().gen()
+}
+
+/// The seed is spread into the 32-byte ChaCha key, ChaCha being a stable, portable algorithm
+fn seeded_prng(seed : u64) -> ChaChaRng {
+    let mut seed_bytes = [0u8; 32];
+    for (i, byte) in seed_bytes.iter_mut().take(8).enumerate() {
+        *byte = (seed >> (8 * i)) as u8;
+    }
+    ChaChaRng::from_seed(seed_bytes)
+}
+
+/// It randomly picks one of the games
+fn pick_game<R : Rng>(games_vec : &[String], prng : &mut R) -> (SudokuBoard, usize) {
+    let index : usize = prng.gen_range(0, games_vec.len());
+    let selected_game = &games_vec[index];
+    (SudokuBoard::new(selected_game.as_str()), index)
+}
+
+// Declare tests module
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,78 @@
+use super::super::{has_unique_solution, check_for_errors, rate};
+use super::Difficulty;
+use super::generator::generate_board;
+use super::{GameSource, seeded_prng, pick_game, generate_game, read_database_sud_file};
+use super::ParseBoardError;
+
+fn count_givens(board : &super::SudokuBoard) -> usize {
+    board.iter().map(|row| row.iter().filter(|cell| **cell != 0).count()).sum()
+}
+
+fn generate_game_test_helper(difficulty : Difficulty, max_givens : usize) {
+    for seed in 0..5 {
+        let board = generate_board(difficulty, &mut seeded_prng(seed));
+        assert!(check_for_errors(&board).is_empty(), "game::database::generator produces a board with errors");
+        assert!(has_unique_solution(&board), "game::database::generator produces a board without a unique solution");
+        assert!(count_givens(&board) <= max_givens, "game::database::generator leaves too many givens");
+        assert_eq!(rate(&board).unwrap().difficulty, difficulty, "game::database::generator produces a board rated at another difficulty");
+    }
+}
+
+#[test]
+fn generate_game_easy() {
+    generate_game_test_helper(Difficulty::Easy, 35);
+}
+
+#[test]
+fn generate_game_medium() {
+    generate_game_test_helper(Difficulty::Medium, 28);
+}
+
+#[test]
+fn generate_game_hard() {
+    generate_game_test_helper(Difficulty::Hard, 25);
+}
+
+#[test]
+fn generate_game_very_hard() {
+    generate_game_test_helper(Difficulty::VeryHard, 25);
+}
+
+#[test]
+fn generate_game_same_seed() {
+    let board_1 = generate_board(Difficulty::Medium, &mut seeded_prng(2018));
+    let board_2 = generate_board(Difficulty::Medium, &mut seeded_prng(2018));
+    assert_eq!(board_1, board_2, "game::database::generator produces different games from the same seed");
+    let board_3 = generate_board(Difficulty::Medium, &mut seeded_prng(2019));
+    assert_ne!(board_1, board_3, "game::database::generator produces the same game from different seeds");
+}
+
+#[test]
+fn generate_game_without_database() {
+    assert!(generate_game(Difficulty::Easy, GameSource::Database, 1, None).is_none(), "game::generate_game() makes up a predefined game without a database");
+    let (board, index) = generate_game(Difficulty::Easy, GameSource::Generator, 1, None).expect("game::generate_game() needs a database to generate a game");
+    assert!(has_unique_solution(&board) && index.is_none(), "game::generate_game() does not generate a game");
+}
+
+#[test]
+fn pick_game_same_seed() {
+    let games : Vec<String> = include_str!("../../../resources/games/easy.sud").lines().map(|line| String::from(line.trim())).collect();
+    for seed in 0..20 {
+        let (board_1, index_1) = pick_game(&games, &mut seeded_prng(seed));
+        let (board_2, index_2) = pick_game(&games, &mut seeded_prng(seed));
+        assert_eq!(index_1, index_2, "game::database::pick_game() picks different games from the same seed");
+        assert_eq!(board_1, board_2, "game::database::pick_game() returns a board not matching the index");
+    }
+}
+
+#[test]
+fn read_database_sud_file_skips_invalid_lines() {
+    let file_contents = format!("{}\n123\n\n{}\n{}x\n", include_str!("../../../resources/games/easy.sud").lines().next().unwrap().trim(),
+        "11".repeat(40) + "1", "0".repeat(80));
+    let (games, invalid_lines) = read_database_sud_file(file_contents.as_bytes()).expect("game::database::read_database_sud_file() fails to read from memory");
+    assert_eq!(games.len(), 1, "game::database::read_database_sud_file() does not keep the valid games");
+    assert_eq!(invalid_lines.len(), 3, "game::database::read_database_sud_file() does not report the invalid lines");
+    assert_eq!(invalid_lines[0], (2, ParseBoardError::WrongLength(3)), "game::database::read_database_sud_file() reports a wrong line or error");
+    assert_eq!(invalid_lines[1].0, 4, "game::database::read_database_sud_file() reports a wrong line");
+    assert_eq!(invalid_lines[2], (5, ParseBoardError::InvalidCharacter{ position: 80, character: 'x' }), "game::database::read_database_sud_file() reports a wrong line or error");
+}
@@ -0,0 +1,150 @@
+use super::{SudokuBoard, solve};
+use std::io;
+use std::path::PathBuf;
+
+/// Exported games are written to this directory within the data directory
+const DIRECTORY_EXPORTS : &str = "exports";
+
+/// The formats a game can be exported to
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ExportFormat {
+    /// A pretty-printed grid per board, for reading and printing
+    Ascii,
+    /// .sdm, a board per line; it only ever holds the givens, as the format has no way to mark the other boards
+    Sdm,
+    /// .ss, a SadMan Simple Sudoku grid, boxes separated by | and ---
+    Ss,
+    /// The XML format of OpenSudoku, a <game data="..."/> element per board
+    OpenSudoku
+}
+
+impl ExportFormat {
+    pub const ALL : [ExportFormat; 4] = [ExportFormat::Ascii, ExportFormat::Sdm, ExportFormat::Ss, ExportFormat::OpenSudoku];
+
+    /// File extension commonly used for the format
+    pub fn extension(&self) -> &'static str {
+        match *self {
+            ExportFormat::Ascii => "txt",
+            ExportFormat::Sdm => "sdm",
+            ExportFormat::Ss => "ss",
+            ExportFormat::OpenSudoku => "opensudoku"
+        }
+    }
+}
+
+/// Writes a game in the given format: its givens, optionally followed by the player's progress and the solution
+/// The progress is left out if the player has not filled in anything, the solution if the givens can not be solved
+/// Only the givens are written as a puzzle, so that importing the file adds just the one; the ASCII format labels the other boards,
+/// .ss and OpenSudoku files keep them in comments, while .sdm files have no comments and lose the progress and the solution;
+/// frontends should say so when they export to .sdm
+pub fn export_game(format : ExportFormat, title : &str, givens : &SudokuBoard, progress : Option<&SudokuBoard>, include_solution : bool) -> String {
+    let mut boards : Vec<(&str, SudokuBoard)> = vec![("Puzzle", *givens)];
+    if let Some(progress) = progress {
+        if progress != givens {
+            boards.push(("Progress", *progress));
+        }
+    }
+    if include_solution {
+        if let Some(solution) = solve(givens) {
+            boards.push(("Solution", solution));
+        }
+    }
+    match format {
+        ExportFormat::Ascii => {
+            let mut result = format!("{}\n", title);
+            for (label, board) in boards.iter() {
+                result.push_str(format!("\n{}\n{}", label, ascii_grid(board)).as_str());
+            }
+            result
+        },
+        ExportFormat::Sdm => format!("{}\n", sdm_line(givens)),
+        ExportFormat::Ss => {
+            // a .ss file holds a single puzzle, the other boards follow as comments
+            let mut result = ss_grid(givens);
+            for (label, board) in boards.iter().skip(1) {
+                result.push_str(format!("#\n# {}\n", label).as_str());
+                for line in ss_grid(board).lines() {
+                    result.push_str(format!("# {}\n", line).as_str());
+                }
+            }
+            result
+        },
+        ExportFormat::OpenSudoku => {
+            let mut result = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opensudoku>\n");
+            result.push_str(format!("  <name>{}</name>\n", escape_xml(title)).as_str());
+            for (label, board) in boards.iter().skip(1) {
+                result.push_str(format!("  <!-- {}: {} -->\n", label, board).as_str());
+            }
+            result.push_str(format!("  <game data=\"{}\" />\n", givens).as_str());
+            result.push_str("</opensudoku>\n");
+            result
+        }
+    }
+}
+
+/// Writes an exported game to a file named after it in the exports directory, replacing an earlier export of the same name
+/// Returns the path of the file
+pub fn save_export(name : &str, format : ExportFormat, contents : &str) -> io::Result<PathBuf> {
+    let mut path_buf = ::util::data_dir();
+    path_buf.push(DIRECTORY_EXPORTS);
+    path_buf.push(name);
+    path_buf.set_extension(format.extension());
+    ::util::write_file_atomically(path_buf.as_path(), contents.as_bytes())?;
+    Ok(path_buf)
+}
+
+fn cell_char(value : u8) -> char {
+    if value == 0 {
+        '.'
+    } else {
+        (b'0' + value) as char
+    }
+}
+
+/// The board as 81 characters, with dots for empty cells
+fn sdm_line(board : &SudokuBoard) -> String {
+    board.iter().flat_map(|row| row.iter()).map(|value| cell_char(*value)).collect()
+}
+
+/// Draws the board with +---+ box borders and spaced out cells
+pub fn ascii_grid(board : &SudokuBoard) -> String {
+    const BORDER : &str = "+-------+-------+-------+\n";
+    let mut result = String::from(BORDER);
+    for (i, row) in board.iter().enumerate() {
+        result.push('|');
+        for (j, value) in row.iter().enumerate() {
+            result.push(' ');
+            result.push(cell_char(*value));
+            if j % 3 == 2 {
+                result.push_str(" |");
+            }
+        }
+        result.push('\n');
+        if i % 3 == 2 {
+            result.push_str(BORDER);
+        }
+    }
+    result
+}
+
+fn ss_grid(board : &SudokuBoard) -> String {
+    let mut result = String::new();
+    for (i, row) in board.iter().enumerate() {
+        for (j, value) in row.iter().enumerate() {
+            result.push(cell_char(*value));
+            if j == 2 || j == 5 {
+                result.push('|');
+            }
+        }
+        result.push('\n');
+        if i == 2 || i == 5 {
+            result.push_str("-----------\n");
+        }
+    }
+    result
+}
+
+/// Escapes the characters that can not appear as they are in XML text and attribute values
+pub fn escape_xml(text : &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,112 @@
+use super::{SudokuBoard, BoardNotes};
+use std::fmt;
+
+/// A single change the player made to a cell: its value, its notes, or both
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Move {
+    pub row: usize,
+    pub col: usize,
+    pub old_value: u8,
+    pub new_value: u8,
+    pub old_notes: u16,
+    pub new_notes: u16
+}
+
+/// Undo/redo history of the player's moves
+/// Moves before the position have been made, the ones after it have been undone and can be redone
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct MoveHistory {
+    moves: Vec<Move>,
+    position: usize
+}
+
+impl MoveHistory {
+    pub fn new() -> MoveHistory {
+        MoveHistory{
+            moves: Vec::new(),
+            position: 0
+        }
+    }
+
+    /// Records a move that has just been made, which discards the moves that could be redone
+    pub fn push(&mut self, player_move : Move) {
+        self.moves.truncate(self.position);
+        self.moves.push(player_move);
+        self.position += 1;
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.position > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.position < self.moves.len()
+    }
+
+    /// Reverts the last move made on the board and notes, returns false if there is nothing to undo
+    pub fn undo(&mut self, board : &mut SudokuBoard, notes : &mut BoardNotes) -> bool {
+        if !self.can_undo() {
+            return false;
+        }
+        self.position -= 1;
+        let player_move = self.moves[self.position];
+        board[player_move.row][player_move.col] = player_move.old_value;
+        notes.set(player_move.row, player_move.col, player_move.old_notes);
+        true
+    }
+
+    /// Makes the last undone move again, returns false if there is nothing to redo
+    pub fn redo(&mut self, board : &mut SudokuBoard, notes : &mut BoardNotes) -> bool {
+        if !self.can_redo() {
+            return false;
+        }
+        let player_move = self.moves[self.position];
+        self.position += 1;
+        board[player_move.row][player_move.col] = player_move.new_value;
+        notes.set(player_move.row, player_move.col, player_move.new_notes);
+        true
+    }
+
+    /// Parses the format produced by to_string(), returns None if it is malformed
+    pub fn parse(history_string : &str) -> Option<MoveHistory> {
+        let mut parts = history_string.split(';');
+        let position = parts.next()?.parse::<usize>().ok()?;
+        let mut moves : Vec<Move> = Vec::new();
+        for part in parts {
+            let fields : Vec<&str> = part.split(',').collect();
+            if fields.len() != 6 {
+                return None;
+            }
+            let player_move = Move{
+                row: fields[0].parse().ok()?,
+                col: fields[1].parse().ok()?,
+                old_value: fields[2].parse().ok()?,
+                new_value: fields[3].parse().ok()?,
+                old_notes: fields[4].parse().ok()?,
+                new_notes: fields[5].parse().ok()?
+            };
+            if player_move.row > 8 || player_move.col > 8 || player_move.old_value > 9 || player_move.new_value > 9 {
+                return None;
+            }
+            moves.push(player_move);
+        }
+        if position > moves.len() {
+            return None;
+        }
+        Some(MoveHistory{
+            moves,
+            position
+        })
+    }
+}
+
+// Used to save the history to a file: the position followed by the moves, separated by semicolons
+impl fmt::Display for MoveHistory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.position)?;
+        for player_move in self.moves.iter() {
+            write!(f, ";{},{},{},{},{},{}", player_move.row, player_move.col, player_move.old_value, player_move.new_value, player_move.old_notes, player_move.new_notes)?;
+        }
+        Ok(())
+    }
+}
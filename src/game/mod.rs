@@ -1,10 +1,21 @@
 // Declare the submodules
+mod collection;
 mod database;
+mod export;
+mod history;
+mod notes;
+mod save_format;
+mod save_slots;
+mod session;
+mod settings;
 mod solver;
+mod statistics;
+mod svg;
 
 use std::ops::{Deref, DerefMut};
 use std::fmt;
-use std::fs::File;
+use std::str::FromStr;
+use std::time::Duration;
 
 /// Type alias for the board matrix
 type SudokuBoardMatrix = [[u8; 9]; 9];
@@ -15,13 +26,84 @@ type SudokuBoardMatrix = [[u8; 9]; 9];
 pub struct SudokuBoard( SudokuBoardMatrix );
 
 impl SudokuBoard {
+    /// Creates a board from a string that is known to be valid, such as one produced by to_string()
+    /// # Panics
+    /// If the string is not a board, see parse_cells
     pub fn new(game_string : &str) -> SudokuBoard {
-        assert_eq!(game_string.len(), 81);
+        match SudokuBoard::parse_cells(game_string) {
+            Ok(board) => board,
+            Err(error) => panic!("invalid board {}: {}", game_string, error)
+        }
+    }
+
+    /// Reads the 81 cells of a board, row by row, without checking whether the values conflict
+    /// Digits 1 to 9 are values, 0 and . are empty cells; whitespace and the grid drawing characters | - + are skipped
+    pub fn parse_cells(game_string : &str) -> Result<SudokuBoard, ParseBoardError> {
         let mut board = [[0u8; 9]; 9];
-        for (i, ch) in game_string.chars().enumerate() {
-            board[i / 9][i % 9] = ch.to_digit(10).unwrap() as u8;
+        let mut cell_count = 0;
+        for (position, ch) in game_string.chars().enumerate() {
+            let value = match ch {
+                '1' ..= '9' => ch.to_digit(10).unwrap() as u8,
+                '0' | '.' => 0,
+                '|' | '-' | '+' => continue,
+                _ if ch.is_whitespace() => continue,
+                _ => return Err(ParseBoardError::InvalidCharacter{ position, character: ch })
+            };
+            if cell_count < 81 {
+                board[cell_count / 9][cell_count % 9] = value;
+            }
+            cell_count += 1;
+        }
+        if cell_count != 81 {
+            return Err(ParseBoardError::WrongLength(cell_count));
+        }
+        Ok(SudokuBoard(board))
+    }
+}
+
+/// Why a string is not a valid Sudoku board
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ParseBoardError {
+    /// The number of cells found instead of 81
+    WrongLength(usize),
+    /// A character that is neither a cell nor a separator, at the given position counted in characters from zero
+    InvalidCharacter{ position: usize, character: char },
+    /// The same value is given twice in a row, column or box, the cells are (row, column) pairs counted from zero
+    ConflictingGivens{ value: u8, first: (usize, usize), second: (usize, usize) }
+}
+
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseBoardError::WrongLength(cell_count) => write!(f, "the board has {} cells instead of 81", cell_count),
+            ParseBoardError::InvalidCharacter{ position, character } => write!(f, "invalid character '{}' at position {}", character, position + 1),
+            ParseBoardError::ConflictingGivens{ value, first, second } => write!(f, "{} is given both at row {}, column {} and at row {}, column {}",
+                value, first.0 + 1, first.1 + 1, second.0 + 1, second.1 + 1)
         }
-        SudokuBoard(board)
+    }
+}
+
+/// Parses a puzzle, the same as parse_cells, but also refuses boards whose givens conflict
+impl FromStr for SudokuBoard {
+    type Err = ParseBoardError;
+
+    fn from_str(game_string : &str) -> Result<SudokuBoard, ParseBoardError> {
+        let board = SudokuBoard::parse_cells(game_string)?;
+        for first in 0..81 {
+            let (first_i, first_j) = (first / 9, first % 9);
+            let value = board[first_i][first_j];
+            if value == 0 {
+                continue;
+            }
+            for second in (first + 1)..81 {
+                let (second_i, second_j) = (second / 9, second % 9);
+                let shares_unit = first_i == second_i || first_j == second_j || (first_i / 3 == second_i / 3 && first_j / 3 == second_j / 3);
+                if shares_unit && board[second_i][second_j] == value {
+                    return Err(ParseBoardError::ConflictingGivens{ value, first: (first_i, first_j), second: (second_i, second_j) });
+                }
+            }
+        }
+        Ok(board)
     }
 }
 
@@ -50,14 +132,14 @@ impl fmt::Debug for SudokuBoard {
             }
             result.push('\n');
         }
-        result.push_str("}");
+        result.push('}');
         write!(f, "{}", result)
     }
 }
 
 // Used to save a game to a file
-impl ToString for SudokuBoard {
-    fn to_string(&self) -> String {
+impl fmt::Display for SudokuBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use std::char::from_digit;
         let mut result = String::new();
         for row in self.iter() {
@@ -65,18 +147,18 @@ impl ToString for SudokuBoard {
                 result.push(from_digit(*el as u32, 10).unwrap());
             }
         }
-        result
+        write!(f, "{}", result)
     }
 }
 
-// Re-export the Difficulty enum from the database module
-pub use self::database::Difficulty;
+// Re-export the Difficulty and GameSource enums from the database module
+pub use self::database::{Difficulty, GameSource};
 
-// Re-export game database initialization from the database module
-pub use self::database::initialize_database;
+// Re-export the predefined games from the database module
+pub use self::database::{Database, DatabaseError, SkippedLine};
 
 // Re-export generate game functionality from the database module
-pub use self::database::generate_game;
+pub use self::database::{generate_game, game_title, random_seed};
 
 /// Internal enum to represent the types of errors that are shown to the user
 pub enum SudokuBoardError {
@@ -138,7 +220,7 @@ pub fn check_for_errors(board : &SudokuBoard) -> Vec<SudokuBoardError> {
 }
 
 /// Finds an unassigned board location
-pub fn find_unassigned_location(board : &SudokuBoard) -> Option<(usize, usize)> {
+fn find_unassigned_location(board : &SudokuBoard) -> Option<(usize, usize)> {
     for (i, row) in board.iter().enumerate() {
         for (j, cell) in row.iter().enumerate() {
             if *cell == 0 {
@@ -150,75 +232,90 @@ pub fn find_unassigned_location(board : &SudokuBoard) -> Option<(usize, usize)>
 }
 
 // Re-export solve game functionality from the solver module
-pub use self::solver::solve;
-
-const FILENAME_SAVED_GAME : &str = "saved_game.sud";
-
-/// Save game to file
-/// Fails silently
-pub fn save_to_file(board_original : &SudokuBoard, board_playing : &SudokuBoard, title : &str) {
-    use std::io::BufWriter;
-    use std::io::Write;
-    let mut path_buf = ::util::exe_dir();
-    path_buf.push(FILENAME_SAVED_GAME);
-    if let Ok(file) = File::create(path_buf.as_path()){
-        let mut writer = BufWriter::new(file);
-        let mut output_data = board_original.to_string();
-		output_data.push('\n');
-		output_data.push_str(board_playing.to_string().as_str());
-		output_data.push('\n');
-		output_data.push_str(title);
-        let _result = writer.write_all(output_data.as_bytes()); // assign to unused variable to avoid the warning
-    }
-}
+pub use self::solver::{solve, count_solutions, has_unique_solution};
+
+// Re-export the technique-based solver, which explains its steps
+pub use self::solver::{Technique, Step, LogicalSolution, LogicalSolver, solve_logically, next_placement};
+
+// Re-export puzzle grading from the solver module
+pub use self::solver::{Rating, rate, technique_score};
+
+// Re-export background solving, which can be cancelled and reports its progress, from the solver module
+pub use self::solver::{SolverJob, SolverOutcome};
+
+// Re-export player notes from the notes module
+pub use self::notes::BoardNotes;
+
+// Re-export the undo/redo history from the history module
+pub use self::history::{Move, MoveHistory};
+
+// Re-export the player's statistics and records from the statistics module
+pub use self::statistics::{Statistics, DifficultyStatistics, save_statistics, load_statistics};
+
+// Re-export puzzle importing and the user collection from the collection module
+pub use self::collection::{ImportFormat, ImportReport, RejectedPuzzle, ParsedPuzzles, CollectionUpdate, import_file, parse_puzzles, add_to_collection, collection_games};
+
+// Re-export exporting games to text and interchange formats from the export module
+pub use self::export::{ExportFormat, export_game, save_export, ascii_grid};
+
+// Re-export printable SVG rendering of boards and pages of puzzles from the svg module
+pub use self::svg::{PrintedPuzzle, board_svg, puzzle_pages_svg, solution_pages_svg};
 
-/// Load saved game from file
-/// Fails silently by returning None
-pub fn load_from_file() -> Option<(SudokuBoard, SudokuBoard, String)> {
-    use std::io::BufReader;
-    use std::io::BufRead;
-    use ::util::is_numeric;
-    let mut path_buf = ::util::exe_dir();
-    path_buf.push(FILENAME_SAVED_GAME);
-    if let Ok(file) = File::open(path_buf.as_path()){
-		let mut file_reader = BufReader::new(file);
-		let mut board_original : Option<SudokuBoard> = None;
-		let mut board_playing : Option<SudokuBoard> = None;
-		let mut title : Option<String> = None;
-		for (index, line) in file_reader.lines().enumerate() {
-			if let Ok(line) = line {
-				if index == 0 || index == 1 {
-					if line.len() == 81 && is_numeric(line.as_str()) {
-						if index == 0 {
-							board_original = Some(SudokuBoard::new(line.as_str()));
-						} else {
-							board_playing = Some(SudokuBoard::new(line.as_str()));
-						}
-					}
-				} else if index == 2 {
-					title = Some(String::from(line.as_str()));
-				} else {
-					break;
-				}
-			} else {
-				break;
-			}
-		}
-		if board_original.is_some() && board_playing.is_some() && title.is_some() {
-			Some((board_original.unwrap(), board_playing.unwrap(), title.unwrap()))
-		} else {
-			None
-		}
-    } else {
-		None
-	}
+// Re-export the game being played, shared by the frontends, from the session module
+pub use self::session::{GameSession, SolverFeedback};
+
+// Re-export the player's preferences from the settings module
+pub use self::settings::{Settings, save_settings, load_settings};
+
+// Re-export the error of loading a saved game from the save_format module
+pub use self::save_format::SaveFileError;
+
+// Re-export the named save slots from the save_slots module
+pub use self::save_slots::{SaveSlot, list_save_slots, save_to_slot, load_from_slot, delete_save_slot, slot_name_for, migrate_legacy_saved_game};
+
+// Re-export choosing where the game keeps its files and finding its resources from the util module
+pub use ::util::{set_data_dir, set_config_dir, find_resource};
+
+/// A game in progress, as it is written to a save file
+pub struct SavedGame {
+    pub board_original: SudokuBoard,
+    pub board_playing: SudokuBoard,
+    pub title: String,
+    pub notes: BoardNotes,
+    pub history: MoveHistory,
+    /// Time the player has spent on the game, whole seconds are saved
+    pub elapsed: Duration,
+    /// Used to keep statistics, unknown for games saved before statistics were introduced
+    pub difficulty: Option<Difficulty>,
+    /// The seed the game was created from, unknown for games saved before it was kept
+    pub seed: Option<u64>,
+    /// Whether typing into a cell takes notes
+    pub notes_mode: bool
 }
 
-pub fn remove_saved_game() {
-	let mut path_buf = ::util::exe_dir();
-    path_buf.push(FILENAME_SAVED_GAME);
-	let _ = ::std::fs::remove_file(path_buf.as_path());
+impl SavedGame {
+    /// The share of the originally empty cells the player has filled in, from 0 to 1
+    pub fn progress(&self) -> f32 {
+        let mut empty_cells = 0;
+        let mut filled_cells = 0;
+        for i in 0..9 {
+            for j in 0..9 {
+                if self.board_original[i][j] == 0 {
+                    empty_cells += 1;
+                    if self.board_playing[i][j] != 0 {
+                        filled_cells += 1;
+                    }
+                }
+            }
+        }
+        if empty_cells == 0 {
+            1.0
+        } else {
+            filled_cells as f32 / empty_cells as f32
+        }
+    }
 }
 
 // Declares the test module
+#[cfg(test)]
 mod tests;
\ No newline at end of file
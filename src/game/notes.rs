@@ -0,0 +1,67 @@
+use std::fmt;
+
+/// Candidate notes (pencil marks) the player keeps for the cells of a board
+/// Bit N of a cell's mask being set means digit N is noted
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct BoardNotes([[u16; 9]; 9]);
+
+impl BoardNotes {
+    pub fn new() -> BoardNotes {
+        BoardNotes([[0u16; 9]; 9])
+    }
+
+    pub fn contains(&self, row : usize, col : usize, value : u8) -> bool {
+        self.0[row][col] & (1 << value) != 0
+    }
+
+    pub fn toggle(&mut self, row : usize, col : usize, value : u8) {
+        self.0[row][col] ^= 1 << value;
+    }
+
+    pub fn get(&self, row : usize, col : usize) -> u16 {
+        self.0[row][col]
+    }
+
+    pub fn set(&mut self, row : usize, col : usize, mask : u16) {
+        self.0[row][col] = mask;
+    }
+
+    pub fn clear(&mut self, row : usize, col : usize) {
+        self.0[row][col] = 0;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|row| row.iter().all(|mask| *mask == 0))
+    }
+
+    /// Parses the format produced by to_string(), returns None if it is malformed
+    pub fn parse(notes_string : &str) -> Option<BoardNotes> {
+        let cells : Vec<&str> = notes_string.split(',').collect();
+        if cells.len() != 81 {
+            return None;
+        }
+        let mut notes = BoardNotes::new();
+        for (index, cell) in cells.iter().enumerate() {
+            for ch in cell.chars() {
+                match ch.to_digit(10) {
+                    Some(value) if value != 0 => notes.0[index / 9][index % 9] |= 1 << value,
+                    _ => return None
+                }
+            }
+        }
+        Some(notes)
+    }
+}
+
+// Used to save notes to a file: the noted digits of each cell, cells separated by commas
+impl fmt::Display for BoardNotes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut cells : Vec<String> = Vec::new();
+        for row in self.0.iter() {
+            for mask in row.iter() {
+                cells.push((1..=9u8).filter(|value| mask & (1 << value) != 0).map(|value| value.to_string()).collect());
+            }
+        }
+        write!(f, "{}", cells.join(","))
+    }
+}
@@ -0,0 +1,221 @@
+use super::{SavedGame, SudokuBoard, BoardNotes, MoveHistory, Difficulty};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// The first line of every save file written in the sectioned format
+const FORMAT_HEADER : &str = "sudoku-save";
+/// Bumped whenever a change to the format can not be read by older versions, newer files are refused
+const FORMAT_VERSION : u32 = 1;
+
+/// Why a save file could not be loaded
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SaveFileError {
+    /// The file does not exist or could not be read
+    Unreadable,
+    /// The file is neither in the sectioned format nor in the older line-based one
+    UnknownFormat,
+    /// The file was written by a newer version of the game
+    UnsupportedVersion(u32),
+    MissingKey(String),
+    InvalidValue(String)
+}
+
+impl fmt::Display for SaveFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveFileError::Unreadable => write!(f, "the save file can not be read"),
+            SaveFileError::UnknownFormat => write!(f, "the file is not a saved game"),
+            SaveFileError::UnsupportedVersion(version) => write!(f, "the game was saved by a newer version (format version {})", version),
+            SaveFileError::MissingKey(key) => write!(f, "the saved game has no {}", key),
+            SaveFileError::InvalidValue(key) => write!(f, "the saved game has an invalid {}", key)
+        }
+    }
+}
+
+/// Save files are made of [section] lines followed by key=value lines, after a header and the format version
+/// Keys the game does not know of are ignored when loading, so new ones can be added without a new format version
+impl fmt::Display for SavedGame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut lines : Vec<String> = Vec::new();
+        lines.push(String::from(FORMAT_HEADER));
+        lines.push(format!("version={}", FORMAT_VERSION));
+
+        lines.push(String::from("[game]"));
+        // the title is the only free text, it must stay on a single line
+        lines.push(format!("title={}", self.title.replace(&['\n', '\r'][..], " ")));
+        if let Some(difficulty) = self.difficulty {
+            lines.push(format!("difficulty={}", difficulty.key()));
+        }
+        if let Some(seed) = self.seed {
+            lines.push(format!("seed={}", seed));
+        }
+        lines.push(format!("elapsed={}", self.elapsed.as_secs()));
+
+        lines.push(String::from("[board]"));
+        lines.push(format!("original={}", self.board_original));
+        lines.push(format!("playing={}", self.board_playing));
+
+        lines.push(String::from("[notes]"));
+        lines.push(format!("cells={}", self.notes));
+
+        lines.push(String::from("[history]"));
+        lines.push(format!("moves={}", self.history));
+
+        lines.push(String::from("[settings]"));
+        lines.push(format!("notes_mode={}", self.notes_mode));
+
+        writeln!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl SavedGame {
+    /// Parses the format produced by to_string(), as well as the line-based format that preceded it
+    pub fn parse(saved_game_string : &str) -> Result<SavedGame, SaveFileError> {
+        let mut lines = saved_game_string.lines();
+        match lines.next() {
+            Some(FORMAT_HEADER) => (),
+            Some(line) if is_board_line(line) => return parse_line_based(saved_game_string),
+            _ => return Err(SaveFileError::UnknownFormat)
+        }
+        let version_line = lines.next().unwrap_or("").trim();
+        if !version_line.starts_with("version=") {
+            return Err(SaveFileError::MissingKey(String::from("format version")));
+        }
+        let version = version_line["version=".len()..].parse::<u32>().map_err(|_| SaveFileError::InvalidValue(String::from("format version")))?;
+        if version > FORMAT_VERSION {
+            return Err(SaveFileError::UnsupportedVersion(version));
+        }
+
+        // values are looked up by "section.key"
+        let mut values : HashMap<String, String> = HashMap::new();
+        let mut section = String::new();
+        for line in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if line.starts_with('[') && line.ends_with(']') {
+                section = String::from(&line[1..line.len() - 1]);
+            } else if let Some(separator) = line.find('=') {
+                values.insert(format!("{}.{}", section, &line[..separator]), String::from(&line[separator + 1..]));
+            } else {
+                return Err(SaveFileError::UnknownFormat);
+            }
+        }
+
+        let required = |key : &str| -> Result<&String, SaveFileError> {
+            values.get(key).ok_or_else(|| SaveFileError::MissingKey(String::from(key)))
+        };
+        let invalid = |key : &str| SaveFileError::InvalidValue(String::from(key));
+
+        let board_original = parse_board(required("board.original")?).ok_or_else(|| invalid("board.original"))?;
+        let board_playing = parse_board(required("board.playing")?).ok_or_else(|| invalid("board.playing"))?;
+        let title = required("game.title")?.clone();
+        let notes = match values.get("notes.cells") {
+            Some(cells) => BoardNotes::parse(cells).ok_or_else(|| invalid("notes.cells"))?,
+            None => BoardNotes::new()
+        };
+        let history = match values.get("history.moves") {
+            Some(moves) => MoveHistory::parse(moves).ok_or_else(|| invalid("history.moves"))?,
+            None => MoveHistory::new()
+        };
+        let elapsed = match values.get("game.elapsed") {
+            Some(seconds) => Duration::from_secs(seconds.parse().map_err(|_| invalid("game.elapsed"))?),
+            None => Duration::from_secs(0)
+        };
+        let difficulty = match values.get("game.difficulty") {
+            Some(key) => Some(Difficulty::from_key(key).ok_or_else(|| invalid("game.difficulty"))?),
+            None => None
+        };
+        let seed = match values.get("game.seed") {
+            Some(seed) => Some(seed.parse().map_err(|_| invalid("game.seed"))?),
+            None => None
+        };
+        let notes_mode = match values.get("settings.notes_mode") {
+            Some(notes_mode) => notes_mode.parse().map_err(|_| invalid("settings.notes_mode"))?,
+            None => false
+        };
+
+        Ok(SavedGame{
+            board_original,
+            board_playing,
+            title,
+            notes,
+            history,
+            elapsed,
+            difficulty,
+            seed,
+            notes_mode
+        })
+    }
+}
+
+fn is_board_line(line : &str) -> bool {
+    line.len() == 81 && ::util::is_numeric(line)
+}
+
+fn parse_board(line : &str) -> Option<SudokuBoard> {
+    // the playing board may contain the player's mistakes, so conflicting values are accepted
+    SudokuBoard::parse_cells(line).ok()
+}
+
+/// Reads the format used before save files had sections: the original board, the playing board and the title,
+/// each on a line of its own, later followed by lines with the notes, the move history, the elapsed seconds and the difficulty
+fn parse_line_based(saved_game_string : &str) -> Result<SavedGame, SaveFileError> {
+    let lines : Vec<&str> = saved_game_string.lines().collect();
+    let invalid = |key : &str| SaveFileError::InvalidValue(String::from(key));
+    if lines.len() < 3 {
+        return Err(SaveFileError::MissingKey(String::from(if lines.len() < 2 { "board.playing" } else { "game.title" })));
+    }
+    let board_original = parse_board(lines[0]).ok_or_else(|| invalid("board.original"))?;
+    let board_playing = parse_board(lines[1]).ok_or_else(|| invalid("board.playing"))?;
+    let title = String::from(lines[2]);
+    let notes = match lines.get(3) {
+        Some(cells) => BoardNotes::parse(cells).ok_or_else(|| invalid("notes.cells"))?,
+        None => BoardNotes::new()
+    };
+    let history = match lines.get(4) {
+        Some(moves) => MoveHistory::parse(moves).ok_or_else(|| invalid("history.moves"))?,
+        None => MoveHistory::new()
+    };
+    let elapsed = match lines.get(5) {
+        Some(seconds) => Duration::from_secs(seconds.parse().map_err(|_| invalid("game.elapsed"))?),
+        None => Duration::from_secs(0)
+    };
+    // the difficulty was saved as its index, an empty line standing for an unknown one
+    let difficulty = match lines.get(6) {
+        Some(index) if !index.is_empty() => Some(index.parse::<usize>().ok()
+            .and_then(|index| Difficulty::ALL.get(index).cloned())
+            .ok_or_else(|| invalid("game.difficulty"))?),
+        _ => None
+    };
+    Ok(SavedGame{
+        board_original,
+        board_playing,
+        title,
+        notes,
+        history,
+        elapsed,
+        difficulty,
+        seed: None,
+        notes_mode: false
+    })
+}
+
+/// Save game to a file, the previously saved game stays intact if writing is interrupted
+pub fn write_saved_game(path : &Path, saved_game : &SavedGame) -> io::Result<()> {
+    ::util::write_file_atomically(path, saved_game.to_string().as_bytes())
+}
+
+/// Load saved game from a file, in either the current or the older line-based format
+pub fn read_saved_game(path : &Path) -> Result<SavedGame, SaveFileError> {
+    use std::io::Read;
+    let mut saved_game_string = String::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut saved_game_string))
+        .map_err(|_| SaveFileError::Unreadable)?;
+    SavedGame::parse(saved_game_string.as_str())
+}
@@ -0,0 +1,135 @@
+use super::SavedGame;
+use super::save_format::{SaveFileError, read_saved_game, write_saved_game};
+use std::cmp::Reverse;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Games in progress are kept in this directory within the data directory, a file per slot
+const DIRECTORY_SAVES : &str = "saves";
+const EXTENSION_SAVED_GAME : &str = "sud";
+/// The single save file used before there were save slots, it was kept next to the executable
+const FILENAME_LEGACY_SAVED_GAME : &str = "saved_game.sud";
+
+/// A saved game along with the name of the slot it is saved in
+pub struct SaveSlot {
+    pub name: String,
+    pub saved_game: SavedGame,
+    /// When the slot was last written to
+    pub last_played: SystemTime
+}
+
+fn saves_dir() -> PathBuf {
+    let mut path_buf = ::util::data_dir();
+    path_buf.push(DIRECTORY_SAVES);
+    path_buf
+}
+
+fn slot_path(saves_dir : &Path, name : &str) -> PathBuf {
+    let mut path_buf = saves_dir.to_path_buf();
+    path_buf.push(name);
+    path_buf.set_extension(EXTENSION_SAVED_GAME);
+    path_buf
+}
+
+/// Names the slot a game is saved in after its title, keeping only characters that are safe in file names
+pub fn slot_name_for(title : &str) -> String {
+    title.chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() || ch == '-' { ch } else { '_' })
+        .collect()
+}
+
+/// Save game to the named slot, an existing game in that slot is overwritten
+pub fn save_to_slot(name : &str, saved_game : &SavedGame) -> io::Result<()> {
+    write_slot(saves_dir().as_path(), name, saved_game).map(|_| ())
+}
+
+/// Writes a game to the named slot of the given saves directory and returns the path of the slot
+fn write_slot(saves_dir : &Path, name : &str, saved_game : &SavedGame) -> io::Result<PathBuf> {
+    let path = slot_path(saves_dir, name);
+    write_saved_game(path.as_path(), saved_game)?;
+    Ok(path)
+}
+
+/// Load saved game from the named slot
+pub fn load_from_slot(name : &str) -> Result<SavedGame, SaveFileError> {
+    read_saved_game(slot_path(saves_dir().as_path(), name).as_path())
+}
+
+pub fn delete_save_slot(name : &str) {
+    let _ = fs::remove_file(slot_path(saves_dir().as_path(), name));
+}
+
+/// Lists the saved games, the most recently played first
+/// Files that can not be read as saved games are left out
+pub fn list_save_slots() -> Vec<SaveSlot> {
+    let mut slots : Vec<SaveSlot> = Vec::new();
+    if let Ok(entries) = fs::read_dir(saves_dir()) {
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            if path.extension().map_or(true, |extension| extension != EXTENSION_SAVED_GAME) {
+                continue;
+            }
+            let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(name) => String::from(name),
+                None => continue
+            };
+            let last_played = match entry.metadata().and_then(|metadata| metadata.modified()) {
+                Ok(last_played) => last_played,
+                Err(_) => continue
+            };
+            if let Ok(saved_game) = read_saved_game(path.as_path()) {
+                slots.push(SaveSlot{
+                    name,
+                    saved_game,
+                    last_played
+                });
+            }
+        }
+    }
+    slots.sort_by_key(|slot| Reverse(slot.last_played));
+    slots
+}
+
+/// Moves the game saved next to the executable by older versions into a slot of its own, returns whether it was moved
+/// Meant to be called once at startup, before the slots are listed
+pub fn migrate_legacy_saved_game() -> bool {
+    let mut legacy_path = ::util::exe_dir();
+    legacy_path.push(FILENAME_LEGACY_SAVED_GAME);
+    migrate_saved_game(legacy_path.as_path(), saves_dir().as_path())
+}
+
+/// Moves a saved game into a slot of the given saves directory, named after its title, returns whether it was moved
+/// An existing slot is never overwritten, the game gets a numbered name instead unless a slot already holds the very same game,
+/// which an earlier attempt has written without managing to remove the old file
+/// The old file is only removed once the slot has been written and reads back as the same game, otherwise it is left for the next attempt
+pub fn migrate_saved_game(legacy_path : &Path, saves_dir : &Path) -> bool {
+    let saved_game = match read_saved_game(legacy_path) {
+        Ok(saved_game) => saved_game,
+        Err(_) => return false
+    };
+    let base_name = slot_name_for(saved_game.title.as_str());
+    let mut name = base_name.clone();
+    let mut number = 1;
+    loop {
+        let path = slot_path(saves_dir, name.as_str());
+        if !path.exists() {
+            break;
+        }
+        if read_saved_game(path.as_path()).map(|existing| existing.to_string() == saved_game.to_string()).unwrap_or(false) {
+            return fs::remove_file(legacy_path).is_ok();
+        }
+        number += 1;
+        name = format!("{}_{}", base_name, number);
+    }
+    let slot_path = match write_slot(saves_dir, name.as_str(), &saved_game) {
+        Ok(slot_path) => slot_path,
+        Err(_) => return false
+    };
+    let is_written = match read_saved_game(slot_path.as_path()) {
+        Ok(written) => written.to_string() == saved_game.to_string(),
+        Err(_) => false
+    };
+    is_written && fs::remove_file(legacy_path).is_ok()
+}
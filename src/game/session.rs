@@ -0,0 +1,405 @@
+use super::{SudokuBoard, BoardNotes, Move, MoveHistory, Difficulty, GameSource, Database, Statistics, SavedGame};
+use super::{SolverJob, SolverOutcome, generate_game, game_title, check_for_errors, find_unassigned_location};
+use super::{save_statistics, save_to_slot, delete_save_slot, slot_name_for};
+use std::io;
+use std::time::{Duration, Instant};
+
+/// How often, in seconds, a game is saved while it is being played, even if the board has not changed, to keep the saved time up to date
+const AUTOSAVE_INTERVAL_SECONDS : u64 = 30;
+
+/// How long, in seconds, the solver may search before the player is told the puzzle is too hard for it or has no solution
+const SOLVER_TIMEOUT_SECONDS : u64 = 10;
+
+/// What the player is told when the solver has not simply solved the puzzle, along with the cells it is about
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SolverFeedback {
+    /// The solution has been filled in, the player's entries in the cells did not match it
+    ContradictingEntries(Vec<(usize, usize)>),
+    /// One of the solutions has been filled in, the cells are the ones another solution fills in differently
+    MultipleSolutions(Vec<(usize, usize)>),
+    /// The cells are givens that conflict, or else empty cells every digit is ruled out of; there may be none of either
+    NoSolution(Vec<(usize, usize)>),
+    /// The solver gave up before it found a solution
+    TimedOut,
+    /// A solution has been filled in, the solver gave up before finding out whether there is another one
+    UniquenessUnknown
+}
+
+impl SolverFeedback {
+    pub fn message(&self) -> &'static str {
+        match *self {
+            SolverFeedback::ContradictingEntries(_) => "Your current entries contradict the solution, the highlighted cells were wrong. The solution has been filled in.",
+            SolverFeedback::MultipleSolutions(_) => "This puzzle has multiple solutions, one of them has been filled in. The highlighted cells can be filled in differently.",
+            SolverFeedback::NoSolution(ref cells) if cells.is_empty() => "This puzzle has no solution.",
+            SolverFeedback::NoSolution(_) => "This puzzle has no solution, the highlighted cells conflict or have no digit left.",
+            SolverFeedback::TimedOut => "The solver has given up, the puzzle is too hard for it or has no solution.",
+            SolverFeedback::UniquenessUnknown => "A solution has been filled in, the solver gave up before finding out whether it is the only one."
+        }
+    }
+
+    /// The cells to highlight, as (row, column) pairs counted from zero
+    pub fn cells(&self) -> &[(usize, usize)] {
+        match *self {
+            SolverFeedback::ContradictingEntries(ref cells) | SolverFeedback::MultipleSolutions(ref cells) | SolverFeedback::NoSolution(ref cells) => cells.as_slice(),
+            SolverFeedback::TimedOut | SolverFeedback::UniquenessUnknown => &[]
+        }
+    }
+}
+
+/// A game being played, with its boards, history, timer and save slot
+/// It is owned by the frontend playing it, the solver works on a copy of the board in a thread of its own and sends its outcome back through a channel
+pub struct GameSession {
+    pub board_original: SudokuBoard,
+    pub board_playing: SudokuBoard,
+    pub notes: BoardNotes,
+    pub notes_mode: bool,
+    pub history: MoveHistory,
+    pub title: String,
+    pub seed: Option<u64>,
+    /// Unknown for games saved before statistics were kept, those are left out of the statistics
+    pub difficulty: Option<Difficulty>,
+    pub save_slot: String,
+    // the game is saved after every change, every AUTOSAVE_INTERVAL_SECONDS while the timer runs, and when the player leaves it
+    needs_saving: bool,
+    last_saved: Instant,
+    // the timer adds up time from when it was last started to the time elapsed before that, it is stopped while paused or once the game is finished
+    elapsed_before_start: Duration,
+    timer_started: Option<Instant>,
+    is_paused: bool,
+    // whether the game has been won, solved or abandoned as far as the statistics are concerned
+    is_recorded: bool,
+    // whether the player has filled the board in, as opposed to the solver
+    is_won: bool,
+    // the board is locked while the solver runs
+    solver: Option<SolverJob>,
+    // kept until the board changes
+    solver_feedback: Option<SolverFeedback>
+}
+
+impl GameSession {
+    /// Starts a new game, see generate_game for how it is created, and like it returns None if predefined games are asked for without a database
+    /// Its start is not recorded in the statistics, that is left to the frontend
+    pub fn new(difficulty : Difficulty, source : GameSource, seed : u64, database : Option<&Database>) -> Option<GameSession> {
+        let (board_original, game_index) = generate_game(difficulty, source, seed, database)?;
+        let title = game_title(difficulty, source, game_index, seed);
+        Some(GameSession{
+            board_original,
+            board_playing: board_original,
+            notes: BoardNotes::new(),
+            notes_mode: false,
+            history: MoveHistory::new(),
+            save_slot: slot_name_for(title.as_str()),
+            title,
+            seed: Some(seed),
+            difficulty: Some(difficulty),
+            needs_saving: true,
+            last_saved: Instant::now(),
+            elapsed_before_start: Duration::from_secs(0),
+            timer_started: Some(Instant::now()),
+            is_paused: false,
+            is_recorded: false,
+            is_won: false,
+            solver: None,
+            solver_feedback: None
+        })
+    }
+
+    /// Continues a game loaded from the given save slot
+    pub fn from_saved_game(saved_game : SavedGame, save_slot : String) -> GameSession {
+        // a finished game has already been recorded and its timer does not run anymore
+        let is_finished = check_for_errors(&saved_game.board_playing).is_empty() && find_unassigned_location(&saved_game.board_playing).is_none();
+        GameSession{
+            board_original: saved_game.board_original,
+            board_playing: saved_game.board_playing,
+            notes: saved_game.notes,
+            notes_mode: saved_game.notes_mode,
+            history: saved_game.history,
+            title: saved_game.title,
+            seed: saved_game.seed,
+            difficulty: saved_game.difficulty,
+            save_slot,
+            needs_saving: false,
+            last_saved: Instant::now(),
+            elapsed_before_start: saved_game.elapsed,
+            timer_started: if is_finished { None } else { Some(Instant::now()) },
+            is_paused: false,
+            is_recorded: is_finished,
+            // a solved game is not kept in its slot, so a finished one has been won
+            is_won: is_finished,
+            solver: None,
+            solver_feedback: None
+        }
+    }
+
+    /// The game as it is written to a save file
+    pub fn to_saved_game(&self) -> SavedGame {
+        SavedGame{
+            board_original: self.board_original,
+            board_playing: self.board_playing,
+            title: self.title.clone(),
+            notes: self.notes,
+            history: self.history.clone(),
+            elapsed: self.elapsed_time(),
+            difficulty: self.difficulty,
+            seed: self.seed,
+            notes_mode: self.notes_mode
+        }
+    }
+
+    /// Time spent on the game so far
+    pub fn elapsed_time(&self) -> Duration {
+        match self.timer_started {
+            Some(timer_started) => self.elapsed_before_start + timer_started.elapsed(),
+            None => self.elapsed_before_start
+        }
+    }
+
+    fn stop_timer(&mut self) {
+        self.elapsed_before_start = self.elapsed_time();
+        self.timer_started = None;
+    }
+
+    /// The timer runs until the game is paused or finished
+    pub fn is_timer_running(&self) -> bool {
+        self.timer_started.is_some()
+    }
+
+    /// Whether the player has solved the puzzle, a game finished by the solver has not been won
+    pub fn is_won(&self) -> bool {
+        self.is_won
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused
+    }
+
+    /// Pausing stops the timer until the game is resumed, a finished game can not be paused
+    pub fn toggle_pause(&mut self) {
+        if self.is_paused {
+            self.is_paused = false;
+            self.timer_started = Some(Instant::now());
+        } else if self.timer_started.is_some() {
+            self.is_paused = true;
+            self.stop_timer();
+            self.needs_saving = true;
+        }
+    }
+
+    pub fn toggle_notes_mode(&mut self) {
+        self.notes_mode = !self.notes_mode;
+        self.needs_saving = true;
+    }
+
+    /// Whether the board is completely and correctly filled in
+    pub fn is_finished(&self) -> bool {
+        check_for_errors(&self.board_playing).is_empty() && find_unassigned_location(&self.board_playing).is_none()
+    }
+
+    /// Keeps a move that has already been made on the board in the history, so it can be undone
+    pub fn push_move(&mut self, board_move : Move) {
+        self.history.push(board_move);
+        self.needs_saving = true;
+        self.solver_feedback = None;
+    }
+
+    /// Enters a value into a cell, leaving its notes as they are, and keeps the move in the history
+    pub fn place(&mut self, row : usize, col : usize, value : u8) {
+        let notes = self.notes.get(row, col);
+        let board_move = Move{
+            row,
+            col,
+            old_value: self.board_playing[row][col],
+            new_value: value,
+            old_notes: notes,
+            new_notes: notes
+        };
+        self.board_playing[row][col] = value;
+        self.push_move(board_move);
+    }
+
+    /// Reverts the last move, returns whether there was one
+    pub fn undo(&mut self) -> bool {
+        let changed = self.history.undo(&mut self.board_playing, &mut self.notes);
+        if changed {
+            self.needs_saving = true;
+            self.solver_feedback = None;
+        }
+        changed
+    }
+
+    /// Makes the last undone move again, returns whether there was one
+    pub fn redo(&mut self) -> bool {
+        let changed = self.history.redo(&mut self.board_playing, &mut self.notes);
+        if changed {
+            self.needs_saving = true;
+            self.solver_feedback = None;
+        }
+        changed
+    }
+
+    /// Clears everything the player has entered, stopping the solver if it runs
+    pub fn reset(&mut self) {
+        self.solver = None;
+        self.board_playing = self.board_original;
+        self.notes = BoardNotes::new();
+        self.history = MoveHistory::new();
+        self.needs_saving = true;
+        self.solver_feedback = None;
+    }
+
+    /// Records how the game ended, only the first outcome of a game counts
+    pub fn record<F : FnOnce(&mut Statistics, Difficulty)>(&mut self, statistics : &mut Statistics, record : F) {
+        if !self.is_recorded {
+            self.is_recorded = true;
+            if let Some(difficulty) = self.difficulty {
+                record(statistics, difficulty);
+                save_statistics(statistics);
+            }
+        }
+    }
+
+    /// Starts solving the puzzle in the background, a solver that is already running is cancelled
+    /// The player's entries stay until the solution has been found, see update
+    pub fn start_solving(&mut self) {
+        self.solver = Some(SolverJob::start(&self.board_original, Duration::from_secs(SOLVER_TIMEOUT_SECONDS)));
+        self.solver_feedback = None;
+    }
+
+    pub fn is_solving(&self) -> bool {
+        self.solver.is_some()
+    }
+
+    /// The running solver, for showing its progress
+    pub fn solver(&self) -> Option<&SolverJob> {
+        self.solver.as_ref()
+    }
+
+    /// Stops the solver, leaving the board as the player left it
+    pub fn cancel_solving(&mut self) {
+        // dropping the job cancels it
+        self.solver = None;
+    }
+
+    /// What the player should be told about how the solver has ended, until the board changes
+    pub fn solver_feedback(&self) -> Option<&SolverFeedback> {
+        self.solver_feedback.as_ref()
+    }
+
+    /// Blocks until the solver has ended, for frontends that have nothing else to do in the meantime; see update for what is done with its outcome
+    pub fn wait_for_solver(&mut self, statistics : &mut Statistics) {
+        if let Some(solver) = self.solver.take() {
+            self.take_solver_outcome(solver.wait(), statistics);
+        }
+    }
+
+    /// A solution replaces whatever the player has entered, and the game counts as solved in the statistics; what the player should know about it is kept as feedback
+    fn take_solver_outcome(&mut self, outcome : SolverOutcome, statistics : &mut Statistics) {
+        let (solution, feedback) = match outcome {
+            SolverOutcome::Solved(solution) => {
+                let wrong_entries = differing_cells(&self.board_original, &self.board_playing, &solution);
+                (Some(solution), if wrong_entries.is_empty() { None } else { Some(SolverFeedback::ContradictingEntries(wrong_entries)) })
+            },
+            SolverOutcome::MultipleSolutions(first, second) => {
+                (Some(first), Some(SolverFeedback::MultipleSolutions(differing_cells(&self.board_original, &second, &first))))
+            },
+            SolverOutcome::NoSolution => (None, Some(SolverFeedback::NoSolution(unsolvable_cells(&self.board_original)))),
+            SolverOutcome::TimedOut(Some(solution)) => (Some(solution), Some(SolverFeedback::UniquenessUnknown)),
+            SolverOutcome::TimedOut(None) => (None, Some(SolverFeedback::TimedOut)),
+            SolverOutcome::Cancelled => (None, None)
+        };
+        if let Some(solution) = solution {
+            self.record(statistics, Statistics::record_solved);
+            self.board_playing = solution;
+            self.notes = BoardNotes::new();
+            self.history = MoveHistory::new();
+            self.needs_saving = true;
+        }
+        self.solver_feedback = feedback;
+    }
+
+    /// Takes in what background work has produced and notices when the game has been won, to be called whenever the frontend updates
+    pub fn update(&mut self, statistics : &mut Statistics) {
+        let outcome = self.solver.as_ref().and_then(|solver| solver.try_outcome());
+        if let Some(outcome) = outcome {
+            self.solver = None;
+            self.take_solver_outcome(outcome, statistics);
+        }
+        if self.timer_started.is_some() && self.is_finished() {
+            self.stop_timer();
+            self.is_won = !self.is_recorded;
+            let time = self.elapsed_time();
+            self.record(statistics, |statistics, difficulty| statistics.record_won(difficulty, time));
+            self.needs_saving = true;
+        }
+    }
+
+    /// Whether the game has changed since it was last saved, or the saved time has fallen behind while the timer runs
+    pub fn needs_saving(&self) -> bool {
+        self.needs_saving || (self.timer_started.is_some() && self.last_saved.elapsed().as_secs() >= AUTOSAVE_INTERVAL_SECONDS)
+    }
+
+    /// Saves the game to its slot, unless it has been won or solved and there is nothing left to continue
+    /// A game that can not be saved is tried again at the next autosave
+    pub fn save(&mut self) -> io::Result<()> {
+        self.needs_saving = false;
+        self.last_saved = Instant::now();
+        if self.is_recorded {
+            delete_save_slot(self.save_slot.as_str());
+            Ok(())
+        } else {
+            save_to_slot(self.save_slot.as_str(), &self.to_saved_game())
+        }
+    }
+
+    /// Gives the game up, it is recorded as abandoned and can not be continued
+    pub fn abandon(mut self, statistics : &mut Statistics) {
+        self.record(statistics, Statistics::record_abandoned);
+        delete_save_slot(self.save_slot.as_str());
+    }
+}
+
+/// The cells that are empty in the puzzle and filled in on the board with something other than the solution
+fn differing_cells(board_original : &SudokuBoard, board : &SudokuBoard, solution : &SudokuBoard) -> Vec<(usize, usize)> {
+    let mut cells = Vec::new();
+    for i in 0..9 {
+        for j in 0..9 {
+            if board_original[i][j] == 0 && board[i][j] != 0 && board[i][j] != solution[i][j] {
+                cells.push((i, j));
+            }
+        }
+    }
+    cells
+}
+
+/// The values in a cell's row, column and box, apart from the cell itself, as a bitmask with bit N standing for digit N
+fn peer_values(board : &SudokuBoard, i : usize, j : usize) -> u16 {
+    let mut mask = 0u16;
+    for k in 0..9 {
+        for &(peer_i, peer_j) in [(i, k), (k, j), (3 * (i / 3) + k / 3, 3 * (j / 3) + k % 3)].iter() {
+            if (peer_i, peer_j) != (i, j) {
+                mask |= 1 << board[peer_i][peer_j];
+            }
+        }
+    }
+    mask
+}
+
+/// Points out why a puzzle has no solution: givens that repeat in a row, column or box, or failing that, empty cells all nine digits are ruled out of
+/// Puzzles whose contradiction only shows up deeper in the search have none of either
+fn unsolvable_cells(board : &SudokuBoard) -> Vec<(usize, usize)> {
+    const ALL_DIGITS : u16 = 0b11_1111_1110;
+    let mut conflicting = Vec::new();
+    let mut blocked = Vec::new();
+    for i in 0..9 {
+        for j in 0..9 {
+            let peers = peer_values(board, i, j);
+            let value = board[i][j];
+            if value != 0 && peers & (1 << value) != 0 {
+                conflicting.push((i, j));
+            } else if value == 0 && peers & ALL_DIGITS == ALL_DIGITS {
+                blocked.push((i, j));
+            }
+        }
+    }
+    if conflicting.is_empty() { blocked } else { conflicting }
+}
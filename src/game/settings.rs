@@ -0,0 +1,77 @@
+use super::GameSource;
+use std::fmt;
+use std::fs::File;
+
+const FILENAME_SETTINGS : &str = "settings.txt";
+
+/// The player's preferences, kept between runs of the game
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Settings {
+    /// Where new games come from
+    pub game_source: GameSource
+}
+
+impl Settings {
+    pub fn new() -> Settings {
+        Settings{
+            game_source: GameSource::Generator
+        }
+    }
+
+    /// Parses the format produced by to_string()
+    /// Unknown keys and invalid values are ignored, leaving those settings at their defaults
+    pub fn parse(settings_string : &str) -> Settings {
+        let mut settings = Settings::new();
+        for line in settings_string.lines() {
+            let mut parts = line.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some("game_source"), Some("generator")) => settings.game_source = GameSource::Generator,
+                (Some("game_source"), Some("database")) => settings.game_source = GameSource::Database,
+                (Some("game_source"), Some("collection")) => settings.game_source = GameSource::Collection,
+                _ => ()
+            }
+        }
+        settings
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings::new()
+    }
+}
+
+// Used to save the settings to a file: a key=value line per setting
+impl fmt::Display for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let game_source = match self.game_source {
+            GameSource::Generator => "generator",
+            GameSource::Database => "database",
+            GameSource::Collection => "collection"
+        };
+        writeln!(f, "game_source={}", game_source)
+    }
+}
+
+/// Save settings to a file in the config directory
+/// Fails silently
+pub fn save_settings(settings : &Settings) {
+    let mut path_buf = ::util::config_dir();
+    path_buf.push(FILENAME_SETTINGS);
+    let _result = ::util::write_file_atomically(path_buf.as_path(), settings.to_string().as_bytes());
+}
+
+/// Load settings from file
+/// A missing file gives the default settings
+pub fn load_settings() -> Settings {
+    use std::io::Read;
+    let mut path_buf = ::util::config_dir();
+    path_buf.push(FILENAME_SETTINGS);
+    let mut settings_string = String::new();
+    if let Ok(mut file) = File::open(path_buf.as_path()) {
+        if file.read_to_string(&mut settings_string).is_ok() {
+            return Settings::parse(settings_string.as_str());
+        }
+    }
+    Settings::new()
+}
@@ -0,0 +1,118 @@
+use super::super::{SudokuBoard, check_for_errors};
+use super::{Grid, JobControl, SearchContext, search};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How a solver job has ended
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SolverOutcome {
+    Solved(SudokuBoard),
+    /// The board has more than one solution, two of them are given
+    MultipleSolutions(SudokuBoard, SudokuBoard),
+    /// The board has no solution
+    NoSolution,
+    /// The solver gave up when its time was up, along with the solution found by then if any
+    /// Without one the board is either very hard or has no solution, with one it is not known whether the solution is unique
+    TimedOut(Option<SudokuBoard>),
+    /// The job was cancelled, a solution found by then is dropped
+    Cancelled
+}
+
+/// A board being solved in a thread of its own, so as not to block the frontend
+/// The search goes on after the first solution to find out whether there is another one, a job that times out in the meantime hands the solution back as TimedOut
+/// The job reports how far it has come, can be cancelled, and gives up once its timeout has passed; dropping the handle cancels it
+pub struct SolverJob {
+    control: Arc<JobControl>,
+    started: Instant,
+    receiver: Receiver<SolverOutcome>
+}
+
+impl SolverJob {
+    pub fn start(board : &SudokuBoard, timeout : Duration) -> SolverJob {
+        let started = Instant::now();
+        let control = Arc::new(JobControl{
+            nodes_explored: AtomicUsize::new(0),
+            is_cancelled: AtomicBool::new(false),
+            deadline: started + timeout
+        });
+        let (sender, receiver) = mpsc::channel();
+        let board = *board;
+        let job_control = control.clone();
+        thread::spawn(move || {
+            // the receiver is gone if the handle has been dropped, then the outcome is not needed anymore
+            let _ = sender.send(run_job(&board, job_control));
+        });
+        SolverJob{
+            control,
+            started,
+            receiver
+        }
+    }
+
+    /// How many positions the search has tried so far, it is updated every thousand or so
+    pub fn nodes_explored(&self) -> usize {
+        self.control.nodes_explored.load(Ordering::Relaxed)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Asks the solver to stop, it ends as cancelled shortly afterwards unless it has already finished
+    pub fn cancel(&self) {
+        self.control.is_cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// The outcome once the job has ended, None while it is still running
+    pub fn try_outcome(&self) -> Option<SolverOutcome> {
+        match self.receiver.try_recv() {
+            Ok(outcome) => Some(outcome),
+            Err(TryRecvError::Empty) => None,
+            // the solver's thread has ended without an outcome, which only happens if it panicked
+            Err(TryRecvError::Disconnected) => Some(SolverOutcome::Cancelled)
+        }
+    }
+
+    /// Blocks until the job has ended
+    pub fn wait(&self) -> SolverOutcome {
+        self.receiver.recv().unwrap_or(SolverOutcome::Cancelled)
+    }
+}
+
+impl Drop for SolverJob {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+fn run_job(board : &SudokuBoard, control : Arc<JobControl>) -> SolverOutcome {
+    if !check_for_errors(board).is_empty() {
+        return SolverOutcome::NoSolution;
+    }
+    let grid = match Grid::from_board(board) {
+        Some(grid) => grid,
+        None => return SolverOutcome::NoSolution
+    };
+    let mut context = SearchContext::new(2);
+    context.job = Some(control.clone());
+    search(grid, &mut context);
+    control.nodes_explored.store(context.nodes_explored, Ordering::Relaxed);
+    job_outcome(&context, control.is_cancelled.load(Ordering::Relaxed))
+}
+
+/// A single solution only counts as solved when the search has run to the end, as only then is it known to be unique
+fn job_outcome(context : &SearchContext, is_cancelled : bool) -> SolverOutcome {
+    match (context.first_solution, context.second_solution) {
+        (Some(first), Some(second)) => SolverOutcome::MultipleSolutions(first, second),
+        _ if context.is_stopped && is_cancelled => SolverOutcome::Cancelled,
+        (solution, None) if context.is_stopped => SolverOutcome::TimedOut(solution),
+        (Some(solution), None) => SolverOutcome::Solved(solution),
+        (None, _) => SolverOutcome::NoSolution
+    }
+}
+
+#[cfg(test)]
+mod tests;
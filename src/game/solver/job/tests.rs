@@ -0,0 +1,34 @@
+use super::super::super::SudokuBoard;
+use super::super::{JobControl, SearchContext, solve};
+use super::{SolverOutcome, run_job, job_outcome};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::time::{Duration, Instant};
+
+#[test]
+fn solver_job_stopped_outcomes() {
+    let board = SudokuBoard::new(&"0".repeat(81));
+    let cancelled = Arc::new(JobControl{
+        nodes_explored: AtomicUsize::new(0),
+        is_cancelled: AtomicBool::new(true),
+        deadline: Instant::now() + Duration::from_secs(60)
+    });
+    assert_eq!(run_job(&board, cancelled), SolverOutcome::Cancelled, "game::solver::SolverJob does not stop when it is cancelled");
+    let out_of_time = Arc::new(JobControl{
+        nodes_explored: AtomicUsize::new(0),
+        is_cancelled: AtomicBool::new(false),
+        deadline: Instant::now()
+    });
+    assert_eq!(run_job(&board, out_of_time), SolverOutcome::TimedOut(None), "game::solver::SolverJob does not stop once its time is up");
+
+    // stopped after the first solution, before the search could tell whether there is a second one
+    let solution = solve(&SudokuBoard::new("530070000600195000098000060800060003400803001700020006060000280000419005000080079")).unwrap();
+    let mut context = SearchContext::new(2);
+    context.solutions_found = 1;
+    context.first_solution = Some(solution);
+    context.is_stopped = true;
+    assert_eq!(job_outcome(&context, false), SolverOutcome::TimedOut(Some(solution)), "game::solver::SolverJob reports a solution as unique although it timed out before knowing");
+    assert_eq!(job_outcome(&context, true), SolverOutcome::Cancelled, "game::solver::SolverJob reports a solution although it was cancelled");
+    context.is_stopped = false;
+    assert_eq!(job_outcome(&context, false), SolverOutcome::Solved(solution), "game::solver::SolverJob does not report a unique solution as solved");
+}
@@ -0,0 +1,477 @@
+use super::super::SudokuBoard;
+use super::{Grid, unit_cells, units_of_cell};
+
+/// Named solving techniques, declared from the easiest to the hardest as ranked by Sudoku Explainer
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Technique {
+    HiddenSingle,
+    NakedSingle,
+    PointingPair,
+    BoxLineReduction,
+    NakedPair,
+    XWing,
+    HiddenPair,
+    NakedTriple,
+    Swordfish,
+    HiddenTriple,
+    XYWing
+}
+
+impl Technique {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Technique::HiddenSingle => "Hidden single",
+            Technique::NakedSingle => "Naked single",
+            Technique::PointingPair => "Pointing pair",
+            Technique::BoxLineReduction => "Box/line reduction",
+            Technique::NakedPair => "Naked pair",
+            Technique::XWing => "X-Wing",
+            Technique::HiddenPair => "Hidden pair",
+            Technique::NakedTriple => "Naked triple",
+            Technique::Swordfish => "Swordfish",
+            Technique::HiddenTriple => "Hidden triple",
+            Technique::XYWing => "XY-Wing"
+        }
+    }
+}
+
+/// A single deduction: the technique, the cells forming the pattern and what it allows
+/// Cells are (row, column) pairs counted from zero, placements and eliminations are (row, column, value) triples
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Step {
+    pub technique: Technique,
+    pub cells: Vec<(usize, usize)>,
+    pub placement: Option<(usize, usize, u8)>,
+    pub eliminations: Vec<(usize, usize, u8)>,
+    pub explanation: String
+}
+
+/// The outcome of solving a board with logical techniques only
+pub struct LogicalSolution {
+    pub steps: Vec<Step>,
+    pub board: SudokuBoard,
+    pub solved: bool
+}
+
+/// Solves a board the way a person would, applying the easiest technique available at every step
+pub struct LogicalSolver {
+    grid: Grid
+}
+
+impl LogicalSolver {
+    /// Returns None if the board has conflicting values
+    pub fn new(board : &SudokuBoard) -> Option<LogicalSolver> {
+        Grid::from_board(board).map(|grid| LogicalSolver{ grid })
+    }
+
+    pub fn board(&self) -> SudokuBoard {
+        self.grid.to_board()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.grid.cells.iter().all(|value| *value != 0)
+    }
+
+    /// Candidates of the given cell as a bitmask, bit N standing for digit N; filled cells have none
+    pub fn candidates(&self, row : usize, col : usize) -> u16 {
+        self.grid.candidates[row * 9 + col]
+    }
+
+    /// Finds the easiest step available, applies it and returns it; None when stuck or solved
+    pub fn next_step(&mut self) -> Option<Step> {
+        let step = self.find_step();
+        if let Some(ref step) = step {
+            self.apply(step);
+        }
+        step
+    }
+
+    fn find_step(&self) -> Option<Step> {
+        let finders : [fn(&Grid) -> Option<Step>; 11] = [
+            find_hidden_single,
+            find_naked_single,
+            find_pointing_pair,
+            find_box_line_reduction,
+            |grid| find_naked_subset(grid, 2),
+            |grid| find_fish(grid, 2),
+            |grid| find_hidden_subset(grid, 2),
+            |grid| find_naked_subset(grid, 3),
+            |grid| find_fish(grid, 3),
+            |grid| find_hidden_subset(grid, 3),
+            find_xy_wing
+        ];
+        for finder in finders.iter() {
+            if let Some(step) = finder(&self.grid) {
+                return Some(step);
+            }
+        }
+        None
+    }
+
+    fn apply(&mut self, step : &Step) {
+        for &(row, col, value) in step.eliminations.iter() {
+            self.grid.candidates[row * 9 + col] &= !(1 << value);
+        }
+        if let Some((row, col, value)) = step.placement {
+            // a contradiction can only come from an invalid board, the following steps then simply find nothing more
+            let _ = self.grid.assign(row * 9 + col, value);
+        }
+    }
+}
+
+/// Applies steps until the board is solved or no technique makes progress
+/// Returns None if the board has conflicting values
+pub fn solve_logically(board : &SudokuBoard) -> Option<LogicalSolution> {
+    let mut solver = LogicalSolver::new(board)?;
+    let mut steps : Vec<Step> = Vec::new();
+    while !solver.is_solved() {
+        match solver.next_step() {
+            Some(step) => steps.push(step),
+            None => break
+        }
+    }
+    Some(LogicalSolution{
+        steps,
+        board: solver.board(),
+        solved: solver.is_solved()
+    })
+}
+
+/// Finds the next cell that can be filled in logically, returning the steps up to and including its placement
+/// Returns None if the board has conflicting values, is already full, or the techniques get stuck before a placement
+pub fn next_placement(board : &SudokuBoard) -> Option<Vec<Step>> {
+    let mut solver = LogicalSolver::new(board)?;
+    let mut steps : Vec<Step> = Vec::new();
+    while let Some(step) = solver.next_step() {
+        let is_placement = step.placement.is_some();
+        steps.push(step);
+        if is_placement {
+            return Some(steps);
+        }
+    }
+    None
+}
+
+fn position(cell : usize) -> (usize, usize) {
+    (cell / 9, cell % 9)
+}
+
+fn cell_name(cell : usize) -> String {
+    format!("r{}c{}", cell / 9 + 1, cell % 9 + 1)
+}
+
+fn cell_names(cells : &[usize]) -> String {
+    cells.iter().map(|cell| cell_name(*cell)).collect::<Vec<String>>().join(", ")
+}
+
+fn unit_name(unit : usize) -> String {
+    if unit < 9 {
+        format!("row {}", unit + 1)
+    } else if unit < 18 {
+        format!("column {}", unit - 9 + 1)
+    } else {
+        format!("box {}", unit - 18 + 1)
+    }
+}
+
+fn mask_digits(mask : u16) -> Vec<u8> {
+    (1..=9u8).filter(|value| mask & (1 << value) != 0).collect()
+}
+
+fn digit_names(mask : u16) -> String {
+    mask_digits(mask).iter().map(|value| value.to_string()).collect::<Vec<String>>().join("/")
+}
+
+fn sees(cell_1 : usize, cell_2 : usize) -> bool {
+    let (units_1, units_2) = (units_of_cell(cell_1), units_of_cell(cell_2));
+    cell_1 != cell_2 && (0..3).any(|k| units_1[k] == units_2[k])
+}
+
+/// Empty cells of the unit that still have the value as a candidate
+fn value_places(grid : &Grid, unit : usize, value : u8) -> Vec<usize> {
+    unit_cells(unit).iter()
+        .filter(|cell| grid.cells[**cell] == 0 && grid.candidates[**cell] & (1 << value) != 0)
+        .cloned()
+        .collect()
+}
+
+fn is_placed_in_unit(grid : &Grid, unit : usize, value : u8) -> bool {
+    unit_cells(unit).iter().any(|cell| grid.cells[*cell] == value)
+}
+
+/// All ways of choosing `size` elements out of `items`
+fn combinations<T : Copy>(items : &[T], size : usize) -> Vec<Vec<T>> {
+    if size == 0 {
+        return vec![Vec::new()];
+    }
+    let mut result : Vec<Vec<T>> = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[(i + 1)..], size - 1) {
+            rest.insert(0, *item);
+            result.push(rest);
+        }
+    }
+    result
+}
+
+/// Lists the candidates out of the mask that the given cells still have, that is what removing the mask from them eliminates
+fn eliminate(grid : &Grid, cells : &[usize], mask : u16) -> Vec<(usize, usize, u8)> {
+    let mut eliminations : Vec<(usize, usize, u8)> = Vec::new();
+    for cell in cells.iter() {
+        if grid.cells[*cell] == 0 {
+            for value in mask_digits(grid.candidates[*cell] & mask) {
+                let (row, col) = position(*cell);
+                eliminations.push((row, col, value));
+            }
+        }
+    }
+    eliminations
+}
+
+fn find_hidden_single(grid : &Grid) -> Option<Step> {
+    // boxes first, hidden singles in boxes are the easiest to spot
+    for unit in (18..27).chain(0..18) {
+        for value in 1..=9u8 {
+            let places = value_places(grid, unit, value);
+            if places.len() == 1 && !is_placed_in_unit(grid, unit, value) {
+                let (row, col) = position(places[0]);
+                return Some(Step{
+                    technique: Technique::HiddenSingle,
+                    cells: vec![(row, col)],
+                    placement: Some((row, col, value)),
+                    eliminations: Vec::new(),
+                    explanation: format!("{} is the only place for {} in {}", cell_name(places[0]), value, unit_name(unit))
+                });
+            }
+        }
+    }
+    None
+}
+
+fn find_naked_single(grid : &Grid) -> Option<Step> {
+    for cell in 0..81 {
+        if grid.cells[cell] == 0 && grid.candidates[cell].count_ones() == 1 {
+            let value = grid.candidates[cell].trailing_zeros() as u8;
+            let (row, col) = position(cell);
+            return Some(Step{
+                technique: Technique::NakedSingle,
+                cells: vec![(row, col)],
+                placement: Some((row, col, value)),
+                eliminations: Vec::new(),
+                explanation: format!("{} is the only candidate left in {}", value, cell_name(cell))
+            });
+        }
+    }
+    None
+}
+
+/// A value confined to one line within a box can be removed from the rest of that line
+fn find_pointing_pair(grid : &Grid) -> Option<Step> {
+    for unit in 18..27 {
+        for value in 1..=9u8 {
+            let places = value_places(grid, unit, value);
+            if places.len() < 2 {
+                continue;
+            }
+            for line_kind in 0..2 {
+                let line = units_of_cell(places[0])[line_kind];
+                if places.iter().all(|cell| units_of_cell(*cell)[line_kind] == line) {
+                    let others : Vec<usize> = unit_cells(line).iter().filter(|cell| units_of_cell(**cell)[2] != unit).cloned().collect();
+                    let eliminations = eliminate(grid, &others, 1 << value);
+                    if !eliminations.is_empty() {
+                        return Some(Step{
+                            technique: Technique::PointingPair,
+                            cells: places.iter().map(|cell| position(*cell)).collect(),
+                            placement: None,
+                            eliminations,
+                            explanation: format!("in {}, {} can only go in {} ({}), so it is removed from the rest of {}", unit_name(unit), value, unit_name(line), cell_names(&places), unit_name(line))
+                        });
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// A value confined to one box within a line can be removed from the rest of that box
+fn find_box_line_reduction(grid : &Grid) -> Option<Step> {
+    for unit in 0..18 {
+        for value in 1..=9u8 {
+            let places = value_places(grid, unit, value);
+            if places.len() < 2 {
+                continue;
+            }
+            let quadrant = units_of_cell(places[0])[2];
+            if places.iter().all(|cell| units_of_cell(*cell)[2] == quadrant) {
+                let others : Vec<usize> = unit_cells(quadrant).iter().filter(|cell| !unit_cells(unit).contains(cell)).cloned().collect();
+                let eliminations = eliminate(grid, &others, 1 << value);
+                if !eliminations.is_empty() {
+                    return Some(Step{
+                        technique: Technique::BoxLineReduction,
+                        cells: places.iter().map(|cell| position(*cell)).collect(),
+                        placement: None,
+                        eliminations,
+                        explanation: format!("in {}, {} can only go in {} ({}), so it is removed from the rest of {}", unit_name(unit), value, unit_name(quadrant), cell_names(&places), unit_name(quadrant))
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+/// N cells of a unit sharing exactly N candidates between them take those values from the rest of the unit
+fn find_naked_subset(grid : &Grid, size : usize) -> Option<Step> {
+    let technique = if size == 2 { Technique::NakedPair } else { Technique::NakedTriple };
+    for unit in 0..27 {
+        let cells : Vec<usize> = unit_cells(unit).iter()
+            .filter(|cell| grid.cells[**cell] == 0 && grid.candidates[**cell].count_ones() as usize <= size)
+            .cloned()
+            .collect();
+        for subset in combinations(&cells, size) {
+            let mask = subset.iter().fold(0u16, |mask, cell| mask | grid.candidates[*cell]);
+            if mask.count_ones() as usize != size {
+                continue;
+            }
+            let others : Vec<usize> = unit_cells(unit).iter().filter(|cell| !subset.contains(cell)).cloned().collect();
+            let eliminations = eliminate(grid, &others, mask);
+            if !eliminations.is_empty() {
+                return Some(Step{
+                    technique,
+                    cells: subset.iter().map(|cell| position(*cell)).collect(),
+                    placement: None,
+                    eliminations,
+                    explanation: format!("{} in {} can only hold {}, so those are removed from the rest of {}", cell_names(&subset), unit_name(unit), digit_names(mask), unit_name(unit))
+                });
+            }
+        }
+    }
+    None
+}
+
+/// N values of a unit confined to the same N cells leave no room there for other candidates
+fn find_hidden_subset(grid : &Grid, size : usize) -> Option<Step> {
+    let technique = if size == 2 { Technique::HiddenPair } else { Technique::HiddenTriple };
+    for unit in 0..27 {
+        let values : Vec<u8> = (1..=9u8)
+            .filter(|value| {
+                let places = value_places(grid, unit, *value).len();
+                places >= 2 && places <= size
+            })
+            .collect();
+        for subset in combinations(&values, size) {
+            let mut cells : Vec<usize> = Vec::new();
+            for value in subset.iter() {
+                for cell in value_places(grid, unit, *value) {
+                    if !cells.contains(&cell) {
+                        cells.push(cell);
+                    }
+                }
+            }
+            if cells.len() != size {
+                continue;
+            }
+            cells.sort();
+            let mask = subset.iter().fold(0u16, |mask, value| mask | (1 << value));
+            let eliminations = eliminate(grid, &cells, !mask);
+            if !eliminations.is_empty() {
+                return Some(Step{
+                    technique,
+                    cells: cells.iter().map(|cell| position(*cell)).collect(),
+                    placement: None,
+                    eliminations,
+                    explanation: format!("in {}, {} can only go in {}, so other candidates are removed from those cells", unit_name(unit), digit_names(mask), cell_names(&cells))
+                });
+            }
+        }
+    }
+    None
+}
+
+/// X-Wing (size 2) and Swordfish (size 3): if in N rows a value is confined to the same N columns, it is removed from
+/// those columns in all other rows; the same holds with rows and columns swapped
+fn find_fish(grid : &Grid, size : usize) -> Option<Step> {
+    let technique = if size == 2 { Technique::XWing } else { Technique::Swordfish };
+    for value in 1..=9u8 {
+        // base lines are rows (units 0 to 8) covered by columns, then the other way around
+        for &(base_offset, cover_kind) in [(0usize, 1usize), (9, 0)].iter() {
+            let base_lines : Vec<usize> = (base_offset..(base_offset + 9))
+                .filter(|line| {
+                    let places = value_places(grid, *line, value).len();
+                    places >= 2 && places <= size
+                })
+                .collect();
+            for subset in combinations(&base_lines, size) {
+                let mut base_cells : Vec<usize> = Vec::new();
+                let mut cover_lines : Vec<usize> = Vec::new();
+                for line in subset.iter() {
+                    for cell in value_places(grid, *line, value) {
+                        base_cells.push(cell);
+                        let cover = units_of_cell(cell)[cover_kind];
+                        if !cover_lines.contains(&cover) {
+                            cover_lines.push(cover);
+                        }
+                    }
+                }
+                if cover_lines.len() != size {
+                    continue;
+                }
+                cover_lines.sort();
+                let others : Vec<usize> = cover_lines.iter()
+                    .flat_map(|cover| unit_cells(*cover).to_vec())
+                    .filter(|cell| !subset.contains(&units_of_cell(*cell)[1 - cover_kind]))
+                    .collect();
+                let eliminations = eliminate(grid, &others, 1 << value);
+                if !eliminations.is_empty() {
+                    base_cells.sort();
+                    let base_names = subset.iter().map(|line| unit_name(*line)).collect::<Vec<String>>().join(", ");
+                    let cover_names = cover_lines.iter().map(|line| unit_name(*line)).collect::<Vec<String>>().join(", ");
+                    return Some(Step{
+                        technique,
+                        cells: base_cells.iter().map(|cell| position(*cell)).collect(),
+                        placement: None,
+                        eliminations,
+                        explanation: format!("in {}, {} can only go in {}, so it is removed from the rest of those lines", base_names, value, cover_names)
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+/// A pivot with candidates XY seeing pincers XZ and YZ: whichever value the pivot takes, one pincer is Z,
+/// so Z is removed from every cell that sees both pincers
+fn find_xy_wing(grid : &Grid) -> Option<Step> {
+    let bivalue : Vec<usize> = (0..81).filter(|cell| grid.cells[*cell] == 0 && grid.candidates[*cell].count_ones() == 2).collect();
+    for pivot in bivalue.iter() {
+        let pivot_mask = grid.candidates[*pivot];
+        let pincers : Vec<usize> = bivalue.iter()
+            .filter(|cell| sees(*pivot, **cell) && (grid.candidates[**cell] & pivot_mask).count_ones() == 1)
+            .cloned()
+            .collect();
+        for pair in combinations(&pincers, 2) {
+            let (mask_1, mask_2) = (grid.candidates[pair[0]], grid.candidates[pair[1]]);
+            let z_mask = mask_1 & mask_2 & !pivot_mask;
+            // the pincers must share Z and cover different values of the pivot
+            if z_mask.count_ones() != 1 || mask_1 & pivot_mask == mask_2 & pivot_mask {
+                continue;
+            }
+            let others : Vec<usize> = (0..81).filter(|cell| *cell != *pivot && sees(pair[0], *cell) && sees(pair[1], *cell)).collect();
+            let eliminations = eliminate(grid, &others, z_mask);
+            if !eliminations.is_empty() {
+                let z = z_mask.trailing_zeros();
+                return Some(Step{
+                    technique: Technique::XYWing,
+                    cells: vec![position(*pivot), position(pair[0]), position(pair[1])],
+                    placement: None,
+                    eliminations,
+                    explanation: format!("pivot {} ({}) with pincers {} ({}) and {} ({}) means one of the pincers is {}, so it is removed from cells seeing both",
+                        cell_name(*pivot), digit_names(pivot_mask), cell_name(pair[0]), digit_names(mask_1), cell_name(pair[1]), digit_names(mask_2), z)
+                });
+            }
+        }
+    }
+    None
+}
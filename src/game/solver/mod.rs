@@ -1,74 +1,305 @@
+// Declare the submodules
+mod job;
+mod logical;
+mod rating;
+
 use super::SudokuBoard;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Instant;
 
-/// Solves the given board if it is solvable and returns the solution found, else it returns None
-pub fn solve(board : &SudokuBoard) -> Option<SudokuBoard> {
-    use super::check_for_errors;
-    if check_for_errors(board).is_empty() {
-        let mut board_clone = board.clone();
-        if solve_board(&mut board_clone) {
-            Some(board_clone)
-        } else {
-            None
+/// Bitmask with bits 1 to 9 set, bit N stands for digit N being a candidate
+const ALL_CANDIDATES : u16 = 0b11_1111_1110;
+
+/// Solver-internal board representation, cells are indexed 0 to 80 row by row
+/// For every empty cell it keeps the bitmask of digits that can still be placed there
+#[derive(Copy, Clone)]
+struct Grid {
+    cells: [u8; 81],
+    candidates: [u16; 81]
+}
+
+impl Grid {
+    /// Builds the grid by placing the givens one by one, returns None if two givens conflict
+    fn from_board(board : &SudokuBoard) -> Option<Grid> {
+        let mut grid = Grid{
+            cells: [0u8; 81],
+            candidates: [ALL_CANDIDATES; 81]
+        };
+        for i in 0..9 {
+            for j in 0..9 {
+                let value = board[i][j];
+                if value != 0 {
+                    let cell = i * 9 + j;
+                    if grid.candidates[cell] & (1 << value) == 0 || !grid.assign(cell, value) {
+                        return None;
+                    }
+                }
+            }
         }
-    } else {
-        None
+        Some(grid)
     }
-}
 
-/// This is a simple backtracking algorithm based on https://www.geeksforgeeks.org/sudoku-backtracking-7/ (visit the site for algorithm explanation, or check other/solver_algorithm/solve_board_algorithm.cpp in the GitHub repository)
-fn solve_board(board : &mut SudokuBoard) -> bool {
-    match super::find_unassigned_location(board) {
-        None => {
-            return true;
-        },
-        Some((row, col)) => {
-            for num in 1..=9 {
-                if is_safe(board, row, col, num) {
-                    board[row][col] = num;
-                    if solve_board(board) {
-                        return true;
+    fn to_board(self) -> SudokuBoard {
+        let mut board = SudokuBoard([[0u8; 9]; 9]);
+        for (cell, value) in self.cells.iter().enumerate() {
+            board[cell / 9][cell % 9] = *value;
+        }
+        board
+    }
+
+    /// Places the value and removes it from the candidates of all peers
+    /// Returns false if that leaves some empty cell without candidates
+    fn assign(&mut self, cell : usize, value : u8) -> bool {
+        let bit = 1u16 << value;
+        self.cells[cell] = value;
+        self.candidates[cell] = 0;
+        for unit in units_of_cell(cell).iter() {
+            for peer in unit_cells(*unit).iter() {
+                if self.cells[*peer] == 0 && self.candidates[*peer] & bit != 0 {
+                    self.candidates[*peer] &= !bit;
+                    if self.candidates[*peer] == 0 {
+                        return false;
                     }
-                    board[row][col] = 0;
                 }
             }
-            return false;
         }
-    };
-}
+        true
+    }
 
-fn used_in_row(board : &SudokuBoard, row : usize, value : u8) -> bool {
-    for el in board[row].iter() {
-        if *el == value {
-            return true;
+    /// Repeatedly applies naked singles (a cell with one candidate) and hidden singles (a digit with one place in a unit)
+    /// Returns false on contradiction
+    fn propagate(&mut self) -> bool {
+        loop {
+            let mut changed = false;
+
+            // naked singles
+            for cell in 0..81 {
+                if self.cells[cell] == 0 {
+                    let candidates = self.candidates[cell];
+                    if candidates == 0 {
+                        return false;
+                    }
+                    if candidates.count_ones() == 1 {
+                        if !self.assign(cell, candidates.trailing_zeros() as u8) {
+                            return false;
+                        }
+                        changed = true;
+                    }
+                }
+            }
+
+            // hidden singles
+            for unit in 0..27 {
+                let cells = unit_cells(unit);
+                let mut placed = 0u16;
+                for cell in cells.iter() {
+                    if self.cells[*cell] != 0 {
+                        placed |= 1 << self.cells[*cell];
+                    }
+                }
+                for value in 1..=9u8 {
+                    let bit = 1u16 << value;
+                    if placed & bit != 0 {
+                        continue;
+                    }
+                    let mut place : Option<usize> = None;
+                    let mut place_count = 0;
+                    for cell in cells.iter() {
+                        if self.cells[*cell] == 0 && self.candidates[*cell] & bit != 0 {
+                            place = Some(*cell);
+                            place_count += 1;
+                        }
+                    }
+                    match place_count {
+                        0 => return false,
+                        1 => {
+                            if !self.assign(place.unwrap(), value) {
+                                return false;
+                            }
+                            placed |= bit;
+                            changed = true;
+                        },
+                        _ => ()
+                    }
+                }
+            }
+
+            if !changed {
+                return true;
+            }
+        }
+    }
+
+    /// Finds the empty cell with the fewest candidates (minimum remaining values), None if the grid is full
+    fn most_constrained_cell(&self) -> Option<usize> {
+        let mut best : Option<usize> = None;
+        let mut best_count = 10;
+        for cell in 0..81 {
+            if self.cells[cell] == 0 {
+                let count = self.candidates[cell].count_ones();
+                if count < best_count {
+                    best = Some(cell);
+                    best_count = count;
+                    if count <= 2 {
+                        break;
+                    }
+                }
+            }
         }
+        best
     }
-    false
 }
 
-fn used_in_col(board : &SudokuBoard, col : usize, value : u8) -> bool {
-    for i in 0..9 {
-        if board[i][col] == value {
-            return true;
+/// Units are the 27 houses of the board: rows 0 to 8, columns 9 to 17, quadrants 18 to 26
+fn unit_cells(unit : usize) -> [usize; 9] {
+    let mut cells = [0usize; 9];
+    for (k, cell) in cells.iter_mut().enumerate() {
+        *cell = if unit < 9 {
+            unit * 9 + k
+        } else if unit < 18 {
+            k * 9 + (unit - 9)
+        } else {
+            let quadrant = unit - 18;
+            (3 * (quadrant / 3) + k / 3) * 9 + 3 * (quadrant % 3) + k % 3
+        };
+    }
+    cells
+}
+
+fn units_of_cell(cell : usize) -> [usize; 3] {
+    let (i, j) = (cell / 9, cell % 9);
+    [i, 9 + j, 18 + 3 * (i / 3) + j / 3]
+}
+
+/// How many nodes a solver job searches between looking at whether it has been cancelled or its time is up
+const JOB_CHECK_INTERVAL : usize = 1024;
+
+/// Shared between a solver job's search and its handle, see SolverJob
+struct JobControl {
+    nodes_explored: AtomicUsize,
+    is_cancelled: AtomicBool,
+    deadline: Instant
+}
+
+/// Book-keeping for a single search
+struct SearchContext {
+    solution_limit: usize,
+    solutions_found: usize,
+    first_solution: Option<SudokuBoard>,
+    second_solution: Option<SudokuBoard>,
+    nodes_explored: usize,
+    // only a solver job can be stopped before the search is complete
+    job: Option<Arc<JobControl>>,
+    is_stopped: bool
+}
+
+impl SearchContext {
+    fn new(solution_limit : usize) -> SearchContext {
+        SearchContext{
+            solution_limit,
+            solutions_found: 0,
+            first_solution: None,
+            second_solution: None,
+            nodes_explored: 0,
+            job: None,
+            is_stopped: false
         }
     }
-    false
+
+    fn is_done(&self) -> bool {
+        self.solutions_found >= self.solution_limit || self.is_stopped
+    }
 }
 
-fn used_in_quadrant(board : &SudokuBoard, qi : usize, qj : usize, value : u8) -> bool {
-    for i in 0..3 {
-        for j in 0..3 {
-            if board[qi + i][qj + j] == value {
-                return true;
+/// Depth-first search that propagates constraints at every node and branches on the most constrained cell
+fn search(mut grid : Grid, context : &mut SearchContext) {
+    context.nodes_explored += 1;
+    if let Some(ref job) = context.job {
+        // the first node is checked too, so a job that is already cancelled or out of time does not search at all
+        if context.nodes_explored % JOB_CHECK_INTERVAL == 1 {
+            job.nodes_explored.store(context.nodes_explored, Ordering::Relaxed);
+            if job.is_cancelled.load(Ordering::Relaxed) || Instant::now() >= job.deadline {
+                context.is_stopped = true;
+                return;
+            }
+        }
+    }
+    if !grid.propagate() {
+        return;
+    }
+    match grid.most_constrained_cell() {
+        None => {
+            if context.first_solution.is_none() {
+                context.first_solution = Some(grid.to_board());
+            } else if context.second_solution.is_none() {
+                context.second_solution = Some(grid.to_board());
+            }
+            context.solutions_found += 1;
+        },
+        Some(cell) => {
+            let candidates = grid.candidates[cell];
+            for value in 1..=9u8 {
+                if candidates & (1 << value) != 0 {
+                    let mut branch = grid;
+                    if branch.assign(cell, value) {
+                        search(branch, context);
+                        if context.is_done() {
+                            return;
+                        }
+                    }
+                }
             }
         }
     }
-    false
 }
 
-fn is_safe(board : &SudokuBoard, row : usize, col : usize, value : u8) -> bool {
-    !used_in_row(board, row, value) && !used_in_col(board, col, value) && !used_in_quadrant(board, row - row % 3, col - col % 3, value)
+/// Solves the given board if it is solvable and returns the solution found, else it returns None
+pub fn solve(board : &SudokuBoard) -> Option<SudokuBoard> {
+    use super::check_for_errors;
+    if check_for_errors(board).is_empty() {
+        if let Some(grid) = Grid::from_board(board) {
+            let mut context = SearchContext::new(1);
+            search(grid, &mut context);
+            context.first_solution
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
+
+// Re-export the technique-based solver
+pub use self::logical::{Technique, Step, LogicalSolution, LogicalSolver, solve_logically, next_placement};
+
+// Re-export puzzle grading
+pub use self::rating::{Rating, rate, technique_score};
+
+// Re-export solving in the background
+pub use self::job::{SolverJob, SolverOutcome};
+
+/// Counts the solutions of the given board, stopping once `limit` of them have been found
+/// Boards with conflicting givens have no solutions
+pub fn count_solutions(board : &SudokuBoard, limit : usize) -> usize {
+    use super::check_for_errors;
+    if limit == 0 || !check_for_errors(board).is_empty() {
+        return 0;
+    }
+    if let Some(grid) = Grid::from_board(board) {
+        let mut context = SearchContext::new(limit);
+        search(grid, &mut context);
+        context.solutions_found
+    } else {
+        0
+    }
+}
+
+/// A proper Sudoku puzzle has exactly one solution
+pub fn has_unique_solution(board : &SudokuBoard) -> bool {
+    count_solutions(board, 2) == 1
 }
 
 // Declare tests module
 #[cfg(test)]
-mod tests;
\ No newline at end of file
+mod tests;
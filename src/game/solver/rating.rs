@@ -0,0 +1,77 @@
+use super::super::{SudokuBoard, Difficulty};
+use super::has_unique_solution;
+use super::logical::{LogicalSolver, Technique};
+
+/// Score given to puzzles that need techniques beyond the implemented ones
+const SCORE_BEYOND_TECHNIQUES : f32 = 5.0;
+
+/// Puzzles solvable with singles alone are graded by the single they need and by the number of steps, every step filling one cell
+/// Hidden singles alone make an easy puzzle and naked singles a medium one, following their Sudoku Explainer scores;
+/// either is a grade harder when more than two thirds of the board has to be filled in, as the singles are then few at the start
+const SINGLES_MAX_STEPS : usize = 81 * 2 / 3;
+
+/// The grade of a puzzle
+#[derive(Clone, PartialEq, Debug)]
+pub struct Rating {
+    pub difficulty: Difficulty,
+    /// Sudoku Explainer style score of the hardest technique needed
+    pub score: f32,
+    /// None if the logical solver gets stuck before solving the puzzle
+    pub hardest_technique: Option<Technique>,
+    pub steps: usize
+}
+
+/// Sudoku Explainer ratings of the techniques
+pub fn technique_score(technique : Technique) -> f32 {
+    match technique {
+        Technique::HiddenSingle => 1.5,
+        Technique::NakedSingle => 2.3,
+        Technique::PointingPair => 2.6,
+        Technique::BoxLineReduction => 2.8,
+        Technique::NakedPair => 3.0,
+        Technique::XWing => 3.2,
+        Technique::HiddenPair => 3.4,
+        Technique::NakedTriple => 3.6,
+        Technique::Swordfish => 3.8,
+        Technique::HiddenTriple => 4.0,
+        Technique::XYWing => 4.2
+    }
+}
+
+/// Grades a puzzle by the hardest technique the logical solver needs and the number of steps it takes
+/// Returns None for boards that do not have exactly one solution, as those are not proper puzzles
+pub fn rate(board : &SudokuBoard) -> Option<Rating> {
+    if !has_unique_solution(board) {
+        return None;
+    }
+    let mut solver = LogicalSolver::new(board)?;
+    let mut hardest : Option<Technique> = None;
+    let mut steps = 0;
+    while let Some(step) = solver.next_step() {
+        steps += 1;
+        if hardest.map_or(true, |hardest| step.technique > hardest) {
+            hardest = Some(step.technique);
+        }
+    }
+    let hardest_technique = if solver.is_solved() { hardest } else { None };
+    let (difficulty, score) = match hardest_technique {
+        None => (Difficulty::VeryHard, SCORE_BEYOND_TECHNIQUES),
+        Some(technique) => {
+            let difficulty = if technique >= Technique::XWing {
+                Difficulty::VeryHard
+            } else if technique >= Technique::PointingPair {
+                Difficulty::Hard
+            } else {
+                // needing naked singles and having many steps each make the puzzle a grade harder than easy
+                Difficulty::ALL[(technique == Technique::NakedSingle) as usize + (steps > SINGLES_MAX_STEPS) as usize]
+            };
+            (difficulty, technique_score(technique))
+        }
+    };
+    Some(Rating{
+        difficulty,
+        score,
+        hardest_technique,
+        steps
+    })
+}
@@ -1,14 +1,19 @@
 use super::super::SudokuBoard;
 use super::super::check_for_errors;
 use super::super::find_unassigned_location;
-use super::solve;
+use super::{solve, count_solutions, has_unique_solution};
+use super::{solve_logically, next_placement, Technique};
+use super::rate;
+use super::{SolverJob, SolverOutcome};
+use super::super::Difficulty;
+use std::time::Duration;
 
 fn solve_game_test_helper(board_str : &str) {
     let board = SudokuBoard::new(board_str);
     if let Some(solution) = solve(&board) {
         let errors = check_for_errors(&solution);
         let unassigned_location = find_unassigned_location(&solution);
-        if !(errors.len() == 0 && unassigned_location.is_none()) {
+        if !(errors.is_empty() && unassigned_location.is_none()) {
             panic!("game::solver::solve() produces invalid solution");
         }
     } else {
@@ -52,4 +57,280 @@ fn solve_game_unsolvable_valid() {
 #[test]
 fn solve_game_empty() {
     solve_game_test_helper("000000000000000000000000000000000000000000000000000000000000000000000000000000000");
-}
\ No newline at end of file
+}
+
+// one of the hardest known puzzles for backtracking solvers, constraint propagation handles it quickly
+#[test]
+fn solve_game_platinum_blonde() {
+    solve_game_test_helper("000000012000000003002300400001800005060070800000009000008500000900040500470006000");
+}
+
+#[test]
+fn solve_game_keeps_givens() {
+    let board = SudokuBoard::new("000080001400000050000006027001400000070900300500000000060008170000305006009040508");
+    let solution = solve(&board).expect("game::solver::solve() fails to solve board");
+    for i in 0..9 {
+        for j in 0..9 {
+            if board[i][j] != 0 {
+                assert_eq!(board[i][j], solution[i][j], "game::solver::solve() overwrites a given cell");
+            }
+        }
+    }
+}
+
+#[test]
+fn solve_game_database_very_hard() {
+    for line in include_str!("../../../resources/games/very_hard.sud").lines() {
+        solve_game_test_helper(line.trim());
+    }
+}
+
+#[test]
+fn count_solutions_unique() {
+    let board = SudokuBoard::new("081000000900040000023571609194086007200000008805207040540710003702000400000400790");
+    assert_eq!(count_solutions(&board, 10), 1, "game::solver::count_solutions() miscounts a proper puzzle");
+    assert!(has_unique_solution(&board), "game::solver::has_unique_solution() rejects a proper puzzle");
+}
+
+#[test]
+fn count_solutions_none() {
+    let board = SudokuBoard::new("516849732307605000809700065135060907472591006968370050253186074684207500791050608");
+    assert_eq!(count_solutions(&board, 10), 0, "game::solver::count_solutions() finds solutions of an unsolvable board");
+    assert!(!has_unique_solution(&board), "game::solver::has_unique_solution() accepts an unsolvable board");
+    let board = SudokuBoard::new("110000000000000000000000000000000000000000000000000000000000000000000000000000000");
+    assert_eq!(count_solutions(&board, 10), 0, "game::solver::count_solutions() finds solutions of a board with conflicting givens");
+}
+
+#[test]
+fn count_solutions_multiple() {
+    // the solved easy game with the digits 1 and 2 removed from two rectangles' corners leaves a deadly pattern
+    let solution = solve(&SudokuBoard::new("081000000900040000023571609194086007200000008805207040540710003702000400000400790")).unwrap();
+    let mut board = solution;
+    let mut removed = 0;
+    'search: for i1 in 0..9 {
+        for i2 in (i1 + 1)..9 {
+            if i1 / 3 != i2 / 3 {
+                continue;
+            }
+            for j1 in 0..9 {
+                for j2 in (j1 + 1)..9 {
+                    if board[i1][j1] == board[i2][j2] && board[i1][j2] == board[i2][j1] {
+                        board[i1][j1] = 0;
+                        board[i1][j2] = 0;
+                        board[i2][j1] = 0;
+                        board[i2][j2] = 0;
+                        removed = 4;
+                        break 'search;
+                    }
+                }
+            }
+        }
+    }
+    assert_eq!(removed, 4, "test board does not contain a rectangle");
+    assert_eq!(count_solutions(&board, 10), 2, "game::solver::count_solutions() miscounts a board with two solutions");
+    assert!(!has_unique_solution(&board), "game::solver::has_unique_solution() accepts an ambiguous board");
+}
+
+#[test]
+fn count_solutions_respects_limit() {
+    let board = SudokuBoard::new("000000000000000000000000000000000000000000000000000000000000000000000000000000000");
+    assert_eq!(count_solutions(&board, 5), 5, "game::solver::count_solutions() does not stop at the limit");
+    assert_eq!(count_solutions(&board, 0), 0, "game::solver::count_solutions() ignores a zero limit");
+}
+
+#[test]
+fn database_games_have_unique_solutions() {
+    let databases = [
+        include_str!("../../../resources/games/easy.sud"),
+        include_str!("../../../resources/games/medium.sud"),
+        include_str!("../../../resources/games/hard.sud"),
+        include_str!("../../../resources/games/very_hard.sud")
+    ];
+    for database in databases.iter() {
+        for line in database.lines() {
+            assert!(has_unique_solution(&SudokuBoard::new(line.trim())), "bundled game {} does not have a unique solution", line);
+        }
+    }
+}
+
+/// Checks every step against the brute-force solution: placements must match it and eliminations must never remove it
+fn solve_logically_test_helper(board_str : &str) -> (bool, Vec<Technique>) {
+    let board = SudokuBoard::new(board_str);
+    let solution = solve(&board).expect("game::solver::solve() fails to solve board");
+    let result = solve_logically(&board).expect("game::solver::solve_logically() rejects a valid board");
+    for step in result.steps.iter() {
+        if let Some((i, j, value)) = step.placement {
+            assert_eq!(solution[i][j], value, "game::solver::solve_logically() makes a wrong placement: {:?}", step);
+        }
+        for &(i, j, value) in step.eliminations.iter() {
+            assert!(solution[i][j] != value, "game::solver::solve_logically() eliminates the solution: {:?}", step);
+        }
+        assert!(step.placement.is_some() || !step.eliminations.is_empty(), "game::solver::solve_logically() makes a step without effect: {:?}", step);
+    }
+    if result.solved {
+        assert_eq!(result.board, solution, "game::solver::solve_logically() reaches a different solution");
+    }
+    (result.solved, result.steps.iter().map(|step| step.technique).collect())
+}
+
+fn solve_logically_technique_test_helper(board_str : &str, technique : Technique) {
+    let (solved, techniques) = solve_logically_test_helper(board_str);
+    assert!(solved, "game::solver::solve_logically() fails to solve board");
+    assert!(techniques.contains(&technique), "game::solver::solve_logically() does not use {}", technique.name());
+}
+
+#[test]
+fn solve_logically_database_games() {
+    let databases = [
+        include_str!("../../../resources/games/easy.sud"),
+        include_str!("../../../resources/games/very_hard.sud")
+    ];
+    for database in databases.iter() {
+        for line in database.lines() {
+            let (solved, _) = solve_logically_test_helper(line.trim());
+            assert!(solved, "game::solver::solve_logically() fails to solve bundled game {}", line);
+        }
+    }
+}
+
+#[test]
+fn solve_logically_singles_only() {
+    let (solved, techniques) = solve_logically_test_helper("081000000900040000023571609194086007200000008805207040540710003702000400000400790");
+    assert!(solved, "game::solver::solve_logically() fails to solve board");
+    assert!(techniques.iter().all(|technique| *technique <= Technique::NakedSingle), "game::solver::solve_logically() uses a harder technique than needed");
+}
+
+#[test]
+fn solve_logically_pointing_pair() {
+    solve_logically_technique_test_helper("050000090006010080398000020003560000000080000000079600500000708070600100800000040", Technique::PointingPair);
+}
+
+#[test]
+fn solve_logically_box_line_reduction() {
+    solve_logically_technique_test_helper("019000200000007000000400008600025900007060400005010000700308000000000007002000630", Technique::BoxLineReduction);
+}
+
+#[test]
+fn solve_logically_naked_pair() {
+    solve_logically_technique_test_helper("000050020000000019060870000025000100000406003047000900000038040570000000080040000", Technique::NakedPair);
+}
+
+#[test]
+fn solve_logically_x_wing() {
+    solve_logically_technique_test_helper("000000000600200005308000009000003408000402700107800000800000604500006001000530000", Technique::XWing);
+}
+
+#[test]
+fn solve_logically_hidden_pair() {
+    solve_logically_technique_test_helper("073000400000004000010000502290700000000801000500002068402000000000903000000000130", Technique::HiddenPair);
+}
+
+#[test]
+fn solve_logically_naked_triple() {
+    solve_logically_technique_test_helper("002000008040000060000012000005001007004060000800500900000780000030020070900000450", Technique::NakedTriple);
+}
+
+#[test]
+fn solve_logically_hidden_triple() {
+    solve_logically_technique_test_helper("008000070000030001010000500400700120000901000023006005006000040900080000080500200", Technique::HiddenTriple);
+}
+
+#[test]
+fn solve_logically_xy_wing() {
+    solve_logically_technique_test_helper("028001000400679500000004000000000103070000040253000000000700000005260008000400090", Technique::XYWing);
+}
+
+// needs techniques beyond the implemented ones, the solver gets stuck but everything it did must still be correct
+#[test]
+fn solve_logically_stuck() {
+    let (solved, _) = solve_logically_test_helper("000000012000000003002300400001800005060070800000009000008500000900040500470006000");
+    assert!(!solved, "game::solver::solve_logically() claims to solve a board beyond its techniques");
+}
+
+#[test]
+fn solve_logically_invalid() {
+    let board = SudokuBoard::new("110000000000000000000000000000000000000000000000000000000000000000000000000000000");
+    assert!(solve_logically(&board).is_none(), "game::solver::solve_logically() accepts a board with conflicting values");
+}
+
+fn rate_database_test_helper(database : &str, lowest : Difficulty, highest : Difficulty) {
+    for line in database.lines() {
+        let rating = rate(&SudokuBoard::new(line.trim())).expect("game::solver::rate() rejects a proper puzzle");
+        assert!(rating.difficulty >= lowest && rating.difficulty <= highest, "bundled game {} is rated {:?}", line, rating.difficulty);
+    }
+}
+
+// the bundled games need singles only, so they spread over the grades singles can get; `sudoku validate` lists the ones rated differently from their file
+#[test]
+fn rate_database_easy() {
+    rate_database_test_helper(include_str!("../../../resources/games/easy.sud"), Difficulty::Easy, Difficulty::Medium);
+}
+
+#[test]
+fn rate_database_medium() {
+    rate_database_test_helper(include_str!("../../../resources/games/medium.sud"), Difficulty::Easy, Difficulty::Medium);
+}
+
+#[test]
+fn rate_database_hard() {
+    rate_database_test_helper(include_str!("../../../resources/games/hard.sud"), Difficulty::Easy, Difficulty::Hard);
+}
+
+#[test]
+fn rate_database_very_hard() {
+    rate_database_test_helper(include_str!("../../../resources/games/very_hard.sud"), Difficulty::Easy, Difficulty::Hard);
+}
+
+#[test]
+fn rate_by_hardest_technique() {
+    let rating = rate(&SudokuBoard::new("028001000400679500000004000000000103070000040253000000000700000005260008000400090")).unwrap();
+    assert_eq!(rating.hardest_technique, Some(Technique::XYWing), "game::solver::rate() reports a wrong hardest technique");
+    assert_eq!(rating.difficulty, Difficulty::VeryHard, "game::solver::rate() underrates an XY-Wing puzzle");
+    let rating = rate(&SudokuBoard::new("019000200000007000000400008600025900007060400005010000700308000000000007002000630")).unwrap();
+    assert_eq!(rating.hardest_technique, Some(Technique::BoxLineReduction), "game::solver::rate() reports a wrong hardest technique");
+    assert_eq!(rating.difficulty, Difficulty::Hard, "game::solver::rate() misrates a box/line reduction puzzle");
+}
+
+#[test]
+fn rate_beyond_techniques() {
+    let rating = rate(&SudokuBoard::new("000000012000000003002300400001800005060070800000009000008500000900040500470006000")).unwrap();
+    assert_eq!(rating.hardest_technique, None, "game::solver::rate() claims to solve a board beyond its techniques");
+    assert_eq!(rating.difficulty, Difficulty::VeryHard, "game::solver::rate() underrates a board beyond its techniques");
+}
+
+#[test]
+fn rate_improper_puzzles() {
+    assert!(rate(&SudokuBoard::new("000000000000000000000000000000000000000000000000000000000000000000000000000000000")).is_none(), "game::solver::rate() rates a board with many solutions");
+    assert!(rate(&SudokuBoard::new("516849732307605000809700065135060907472591006968370050253186074684207500791050608")).is_none(), "game::solver::rate() rates an unsolvable board");
+}
+
+#[test]
+fn next_placement_after_eliminations() {
+    let board = SudokuBoard::new("028001000400679500000004000000000103070000040253000000000700000005260008000400090");
+    let solution = solve(&board).unwrap();
+    let mut board_playing = board;
+    // follow the hints all the way, each must end with a correct placement and only the last step may place a value
+    while let Some(steps) = next_placement(&board_playing) {
+        let (last, rest) = steps.split_last().unwrap();
+        assert!(rest.iter().all(|step| step.placement.is_none()), "game::solver::next_placement() returns more than one placement");
+        let (i, j, value) = last.placement.expect("game::solver::next_placement() does not end with a placement");
+        assert_eq!(solution[i][j], value, "game::solver::next_placement() makes a wrong placement");
+        board_playing[i][j] = value;
+    }
+    assert_eq!(board_playing, solution, "following game::solver::next_placement() does not solve the board");
+}
+
+#[test]
+fn solver_job_outcomes() {
+    let board = SudokuBoard::new("530070000600195000098000060800060003400803001700020006060000280000419005000080079");
+    let job = SolverJob::start(&board, Duration::from_secs(60));
+    assert_eq!(job.wait(), SolverOutcome::Solved(solve(&board).unwrap()), "game::solver::SolverJob does not solve the board");
+    assert!(job.nodes_explored() > 0, "game::solver::SolverJob does not report the nodes it has explored");
+    let unsolvable = SudokuBoard::new("516849732307605000809700065135060907472591006968370050253186074684207500791050608");
+    assert_eq!(SolverJob::start(&unsolvable, Duration::from_secs(60)).wait(), SolverOutcome::NoSolution, "game::solver::SolverJob solves an unsolvable board");
+    match SolverJob::start(&SudokuBoard::new(&"0".repeat(81)), Duration::from_secs(60)).wait() {
+        SolverOutcome::MultipleSolutions(first, second) => assert!(first != second, "game::solver::SolverJob gives the same solution twice"),
+        outcome => panic!("game::solver::SolverJob does not find that an empty board has multiple solutions, got {:?}", outcome)
+    }
+    assert_eq!(SolverJob::start(&board, Duration::from_secs(0)).wait(), SolverOutcome::TimedOut(None), "game::solver::SolverJob does not give up once its time is up");
+}
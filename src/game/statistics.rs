@@ -0,0 +1,167 @@
+use super::Difficulty;
+use std::fmt;
+use std::fs::File;
+use std::time::Duration;
+
+const FILENAME_STATISTICS : &str = "statistics.txt";
+
+/// What happened to the games of a single difficulty
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct DifficultyStatistics {
+    pub games_started: u32,
+    pub games_won: u32,
+    /// Games left with the "Quit game" button before they were finished
+    pub games_abandoned: u32,
+    /// Games given up on by letting the solver fill the board in
+    pub games_solved: u32,
+    pub best_time: Option<Duration>,
+    /// Sum of the times of all won games, used for the average
+    pub total_time: Duration,
+    /// Games won in a row, abandoning a game or using the solver breaks the streak
+    pub current_streak: u32,
+    pub best_streak: u32
+}
+
+impl DifficultyStatistics {
+    pub fn new() -> DifficultyStatistics {
+        DifficultyStatistics{
+            games_started: 0,
+            games_won: 0,
+            games_abandoned: 0,
+            games_solved: 0,
+            best_time: None,
+            total_time: Duration::from_secs(0),
+            current_streak: 0,
+            best_streak: 0
+        }
+    }
+
+    pub fn average_time(&self) -> Option<Duration> {
+        if self.games_won == 0 {
+            None
+        } else {
+            Some(self.total_time / self.games_won)
+        }
+    }
+
+    /// Parses the format produced by to_string(), returns None if it is malformed
+    fn parse(statistics_string : &str) -> Option<DifficultyStatistics> {
+        let fields : Vec<&str> = statistics_string.split(',').collect();
+        if fields.len() != 8 {
+            return None;
+        }
+        Some(DifficultyStatistics{
+            games_started: fields[0].parse().ok()?,
+            games_won: fields[1].parse().ok()?,
+            games_abandoned: fields[2].parse().ok()?,
+            games_solved: fields[3].parse().ok()?,
+            best_time: if fields[4].is_empty() { None } else { Some(Duration::from_secs(fields[4].parse().ok()?)) },
+            total_time: Duration::from_secs(fields[5].parse().ok()?),
+            current_streak: fields[6].parse().ok()?,
+            best_streak: fields[7].parse().ok()?
+        })
+    }
+}
+
+// Used to save the statistics to a file: the fields separated by commas, times in whole seconds and an empty field for no best time
+impl fmt::Display for DifficultyStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let best_time = match self.best_time {
+            Some(best_time) => best_time.as_secs().to_string(),
+            None => String::new()
+        };
+        write!(f, "{},{},{},{},{},{},{},{}", self.games_started, self.games_won, self.games_abandoned, self.games_solved,
+            best_time, self.total_time.as_secs(), self.current_streak, self.best_streak)
+    }
+}
+
+/// The player's statistics and personal records for every difficulty
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Statistics([DifficultyStatistics; 4]);
+
+impl Statistics {
+    pub fn new() -> Statistics {
+        Statistics([DifficultyStatistics::new(); 4])
+    }
+
+    pub fn get(&self, difficulty : Difficulty) -> &DifficultyStatistics {
+        &self.0[difficulty as usize]
+    }
+
+    pub fn record_started(&mut self, difficulty : Difficulty) {
+        self.0[difficulty as usize].games_started += 1;
+    }
+
+    pub fn record_won(&mut self, difficulty : Difficulty, time : Duration) {
+        let statistics = &mut self.0[difficulty as usize];
+        // only whole seconds are saved, so records are kept in whole seconds as well
+        let time = Duration::from_secs(time.as_secs());
+        statistics.games_won += 1;
+        statistics.total_time += time;
+        if statistics.best_time.map_or(true, |best_time| time < best_time) {
+            statistics.best_time = Some(time);
+        }
+        statistics.current_streak += 1;
+        if statistics.current_streak > statistics.best_streak {
+            statistics.best_streak = statistics.current_streak;
+        }
+    }
+
+    pub fn record_abandoned(&mut self, difficulty : Difficulty) {
+        let statistics = &mut self.0[difficulty as usize];
+        statistics.games_abandoned += 1;
+        statistics.current_streak = 0;
+    }
+
+    pub fn record_solved(&mut self, difficulty : Difficulty) {
+        let statistics = &mut self.0[difficulty as usize];
+        statistics.games_solved += 1;
+        statistics.current_streak = 0;
+    }
+
+    /// Parses the format produced by to_string(), returns None if it is malformed
+    pub fn parse(statistics_string : &str) -> Option<Statistics> {
+        let lines : Vec<&str> = statistics_string.lines().collect();
+        if lines.len() != Difficulty::ALL.len() {
+            return None;
+        }
+        let mut statistics = Statistics::new();
+        for (index, line) in lines.iter().enumerate() {
+            statistics.0[index] = DifficultyStatistics::parse(line)?;
+        }
+        Some(statistics)
+    }
+}
+
+// Used to save the statistics to a file: a line per difficulty, from the easiest to the hardest
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lines : Vec<String> = self.0.iter().map(|statistics| statistics.to_string()).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// Save statistics to a file in the data directory, the previous statistics stay intact if writing is interrupted
+/// Fails silently
+pub fn save_statistics(statistics : &Statistics) {
+    let mut path_buf = ::util::data_dir();
+    path_buf.push(FILENAME_STATISTICS);
+    let _result = ::util::write_file_atomically(path_buf.as_path(), statistics.to_string().as_bytes());
+}
+
+/// Load statistics from file
+/// A missing or malformed file gives empty statistics
+pub fn load_statistics() -> Statistics {
+    use std::io::Read;
+    let mut path_buf = ::util::data_dir();
+    path_buf.push(FILENAME_STATISTICS);
+    let mut statistics_string = String::new();
+    if let Ok(mut file) = File::open(path_buf.as_path()) {
+        if file.read_to_string(&mut statistics_string).is_ok() {
+            if let Some(statistics) = Statistics::parse(statistics_string.as_str()) {
+                return statistics;
+            }
+        }
+    }
+    Statistics::new()
+}
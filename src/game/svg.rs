@@ -0,0 +1,122 @@
+use super::{SudokuBoard, solve};
+use super::export::escape_xml;
+
+/// Printed pages are A4 portrait, all lengths are in millimetres
+const PAGE_WIDTH : f64 = 210.0;
+const PAGE_HEIGHT : f64 = 297.0;
+const PAGE_MARGIN : f64 = 15.0;
+
+/// Givens are drawn in black and the player's entries in the blue the sudoku_board widget uses for them
+const COLOR_GIVEN : &str = "#000000";
+const COLOR_ENTRY : &str = "#7777dd";
+
+/// A puzzle to be printed in a batch, along with what it is labeled with
+pub struct PrintedPuzzle {
+    pub title: String,
+    pub seed: Option<u64>,
+    pub board: SudokuBoard
+}
+
+/// Renders a board as a standalone SVG document
+/// With entries, the cells the player has filled in are drawn in a different colour than the givens
+pub fn board_svg(givens : &SudokuBoard, entries : Option<&SudokuBoard>) -> String {
+    // the margin keeps the outer half of the border lines inside the document
+    let size = 100.0;
+    let margin = 2.0;
+    let mut svg = svg_header(size + 2.0 * margin, size + 2.0 * margin);
+    draw_board(&mut svg, margin, margin, size, givens, entries);
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Lays the puzzles out six to a page, two across and three down, each under its title and seed
+/// Returns an SVG document per page
+pub fn puzzle_pages_svg(puzzles : &[PrintedPuzzle]) -> Vec<String> {
+    layout_pages(puzzles, 2, 3, |svg, puzzle, x, y, width, height| {
+        let board_size = (width - 20.0).min(height - 19.0);
+        draw_text(svg, x + width / 2.0, y + 5.0, 4.5, puzzle.title.as_str());
+        if let Some(seed) = puzzle.seed {
+            draw_text(svg, x + width / 2.0, y + 10.0, 3.5, format!("Seed: {}", seed).as_str());
+        }
+        draw_board(svg, x + (width - board_size) / 2.0, y + 14.0, board_size, &puzzle.board, None);
+    })
+}
+
+/// Lays the solutions of the puzzles out twelve to a page, three across and four down, each under its puzzle's title
+/// The givens are drawn in black and the rest of the solution in the colour of entries, puzzles that can not be solved are marked as such
+/// Returns an SVG document per page
+pub fn solution_pages_svg(puzzles : &[PrintedPuzzle]) -> Vec<String> {
+    layout_pages(puzzles, 3, 4, |svg, puzzle, x, y, width, height| {
+        let board_size = (width - 12.0).min(height - 14.0);
+        draw_text(svg, x + width / 2.0, y + 5.0, 3.5, puzzle.title.as_str());
+        match solve(&puzzle.board) {
+            Some(solution) => draw_board(svg, x + (width - board_size) / 2.0, y + 9.0, board_size, &puzzle.board, Some(&solution)),
+            None => draw_text(svg, x + width / 2.0, y + 9.0 + board_size / 2.0, 3.5, "No solution")
+        }
+    })
+}
+
+/// Splits the puzzles into pages of a grid of equally sized slots, drawing each puzzle with draw_slot(svg, puzzle, x, y, width, height)
+fn layout_pages<F>(puzzles : &[PrintedPuzzle], columns : usize, rows : usize, draw_slot : F) -> Vec<String>
+    where F : Fn(&mut String, &PrintedPuzzle, f64, f64, f64, f64) {
+    let slot_width = (PAGE_WIDTH - 2.0 * PAGE_MARGIN) / columns as f64;
+    let slot_height = (PAGE_HEIGHT - 2.0 * PAGE_MARGIN) / rows as f64;
+    puzzles.chunks(columns * rows).map(|page_puzzles| {
+        let mut svg = svg_header(PAGE_WIDTH, PAGE_HEIGHT);
+        svg.push_str(format!("<rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>\n", PAGE_WIDTH, PAGE_HEIGHT).as_str());
+        for (index, puzzle) in page_puzzles.iter().enumerate() {
+            let x = PAGE_MARGIN + (index % columns) as f64 * slot_width;
+            let y = PAGE_MARGIN + (index / columns) as f64 * slot_height;
+            draw_slot(&mut svg, puzzle, x, y, slot_width, slot_height);
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }).collect()
+}
+
+fn svg_header(width : f64, height : f64) -> String {
+    format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}mm\" height=\"{1}mm\" viewBox=\"0 0 {0} {1}\">\n", width, height)
+}
+
+fn draw_text(svg : &mut String, x : f64, y : f64, font_size : f64, text : &str) {
+    svg.push_str(format!("<text x=\"{:.2}\" y=\"{:.2}\" font-family=\"sans-serif\" font-size=\"{:.2}\" text-anchor=\"middle\">{}</text>\n",
+        x, y, font_size, escape_xml(text)).as_str());
+}
+
+/// Draws the board as a square with its top left corner at (x, y), with thick lines around the boxes and thin ones between the cells
+fn draw_board(svg : &mut String, x : f64, y : f64, size : f64, givens : &SudokuBoard, entries : Option<&SudokuBoard>) {
+    let cell_size = size / 9.0;
+    let thick_width = 0.012 * size;
+    let thin_width = 0.004 * size;
+    svg.push_str("<g>\n");
+    svg.push_str(format!("<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"#ffffff\"/>\n", x, y, size, size).as_str());
+    for i in 0..9 {
+        for j in 0..9 {
+            let (value, color) = match entries {
+                Some(entries) if givens[i][j] == 0 => (entries[i][j], COLOR_ENTRY),
+                _ => (givens[i][j], COLOR_GIVEN)
+            };
+            if value != 0 {
+                // the baseline is lowered by about a third of the font size to center the digit vertically
+                svg.push_str(format!("<text x=\"{:.2}\" y=\"{:.2}\" font-family=\"sans-serif\" font-size=\"{:.2}\" text-anchor=\"middle\" fill=\"{}\">{}</text>\n",
+                    x + (j as f64 + 0.5) * cell_size, y + (i as f64 + 0.5) * cell_size + 0.25 * cell_size, 0.7 * cell_size, color, value).as_str());
+            }
+        }
+    }
+    // thin lines first, so the thick ones are drawn over them where they meet
+    for thick in [false, true].iter() {
+        for k in 0..10 {
+            if (k % 3 == 0) != *thick {
+                continue;
+            }
+            let width = if *thick { thick_width } else { thin_width };
+            let offset = k as f64 * cell_size;
+            svg.push_str(format!("<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"#000000\" stroke-width=\"{:.2}\" stroke-linecap=\"square\"/>\n",
+                x + offset, y, x + offset, y + size, width).as_str());
+            svg.push_str(format!("<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"#000000\" stroke-width=\"{:.2}\" stroke-linecap=\"square\"/>\n",
+                x, y + offset, x + size, y + offset, width).as_str());
+        }
+    }
+    svg.push_str("</g>\n");
+}
@@ -1,6 +1,16 @@
 use super::SudokuBoard;
 use super::SudokuBoardError;
+use super::ParseBoardError;
 use super::check_for_errors;
+use super::BoardNotes;
+use super::{Move, MoveHistory};
+use super::{Statistics, DifficultyStatistics, Difficulty};
+use super::{SavedGame, SaveFileError, slot_name_for};
+use super::{Settings, GameSource};
+use super::{ExportFormat, ImportFormat, export_game, parse_puzzles, solve};
+use super::{PrintedPuzzle, board_svg, puzzle_pages_svg, solution_pages_svg};
+use super::{GameSession, SolverFeedback};
+use std::time::Duration;
 
 #[test]
 fn board_marshal_unmarshal_inverse_check() {
@@ -91,4 +101,447 @@ fn check_board_errors_row_column_quadrant() {
         (SudokuBoardError::Row(_), SudokuBoardError::Column(_), SudokuBoardError::Quadrant(_, _)) => (),
         _ => panic!("game::check_for_errors() returns incorrect errors")
     };
-}
\ No newline at end of file
+}
+
+#[test]
+fn notes_marshal_unmarshal_inverse_check() {
+    let mut notes_1 = BoardNotes::new();
+    notes_1.toggle(0, 0, 1);
+    notes_1.toggle(0, 0, 9);
+    notes_1.toggle(4, 7, 5);
+    notes_1.toggle(8, 8, 3);
+    let notes_2 = BoardNotes::parse(notes_1.to_string().as_str()).expect("game::BoardNotes::parse() rejects its own output");
+    assert_eq!(notes_1, notes_2, "marshalling and unmarshalling of game::BoardNotes are not inverse operations");
+    assert!(BoardNotes::parse(BoardNotes::new().to_string().as_str()).unwrap().is_empty(), "empty game::BoardNotes do not survive marshalling");
+}
+
+#[test]
+fn notes_toggle() {
+    let mut notes = BoardNotes::new();
+    notes.toggle(2, 3, 4);
+    assert!(notes.contains(2, 3, 4), "game::BoardNotes::toggle() does not add a note");
+    notes.toggle(2, 3, 4);
+    assert!(!notes.contains(2, 3, 4) && notes.is_empty(), "game::BoardNotes::toggle() does not remove a note");
+}
+
+#[test]
+fn notes_parse_invalid() {
+    assert!(BoardNotes::parse("123,4").is_none(), "game::BoardNotes::parse() accepts too few cells");
+    let mut cells = vec![""; 81];
+    cells[10] = "1a";
+    assert!(BoardNotes::parse(cells.join(",").as_str()).is_none(), "game::BoardNotes::parse() accepts an invalid character");
+    cells[10] = "0";
+    assert!(BoardNotes::parse(cells.join(",").as_str()).is_none(), "game::BoardNotes::parse() accepts a zero note");
+}
+
+fn history_test_moves() -> (Move, Move) {
+    let move_1 = Move{ row: 0, col: 0, old_value: 0, new_value: 5, old_notes: 0, new_notes: 0 };
+    let move_2 = Move{ row: 3, col: 8, old_value: 0, new_value: 0, old_notes: 0, new_notes: 1 << 7 };
+    (move_1, move_2)
+}
+
+#[test]
+fn history_undo_redo() {
+    let mut board = SudokuBoard::new("000000000000000000000000000000000000000000000000000000000000000000000000000000000");
+    let mut notes = BoardNotes::new();
+    let (move_1, move_2) = history_test_moves();
+    let mut history = MoveHistory::new();
+    board[0][0] = 5;
+    history.push(move_1);
+    notes.toggle(3, 8, 7);
+    history.push(move_2);
+
+    assert!(history.undo(&mut board, &mut notes), "game::MoveHistory::undo() refuses to undo");
+    assert!(notes.is_empty() && board[0][0] == 5, "game::MoveHistory::undo() reverts the wrong move");
+    assert!(history.undo(&mut board, &mut notes), "game::MoveHistory::undo() refuses to undo");
+    assert_eq!(board[0][0], 0, "game::MoveHistory::undo() does not revert the value");
+    assert!(!history.undo(&mut board, &mut notes), "game::MoveHistory::undo() undoes past the first move");
+
+    assert!(history.redo(&mut board, &mut notes), "game::MoveHistory::redo() refuses to redo");
+    assert_eq!(board[0][0], 5, "game::MoveHistory::redo() does not restore the value");
+    // a new move discards what could be redone
+    history.push(Move{ row: 1, col: 1, old_value: 0, new_value: 2, old_notes: 0, new_notes: 0 });
+    assert!(!history.can_redo(), "game::MoveHistory::push() keeps undone moves");
+}
+
+#[test]
+fn history_marshal_unmarshal_inverse_check() {
+    let (move_1, move_2) = history_test_moves();
+    let mut history_1 = MoveHistory::new();
+    history_1.push(move_1);
+    history_1.push(move_2);
+    let mut board = SudokuBoard::new("500000000000000000000000000000000000000000000000000000000000000000000000000000000");
+    let mut notes = BoardNotes::new();
+    history_1.undo(&mut board, &mut notes);
+    let history_2 = MoveHistory::parse(history_1.to_string().as_str()).expect("game::MoveHistory::parse() rejects its own output");
+    assert_eq!(history_1, history_2, "marshalling and unmarshalling of game::MoveHistory are not inverse operations");
+    assert_eq!(MoveHistory::parse(MoveHistory::new().to_string().as_str()), Some(MoveHistory::new()), "empty game::MoveHistory does not survive marshalling");
+}
+
+#[test]
+fn history_parse_invalid() {
+    assert!(MoveHistory::parse("").is_none(), "game::MoveHistory::parse() accepts an empty string");
+    assert!(MoveHistory::parse("2;0,0,0,5,0,0").is_none(), "game::MoveHistory::parse() accepts a position past the last move");
+    assert!(MoveHistory::parse("1;9,0,0,5,0,0").is_none(), "game::MoveHistory::parse() accepts a cell outside the board");
+    assert!(MoveHistory::parse("1;0,0,0,5").is_none(), "game::MoveHistory::parse() accepts a move with missing fields");
+}
+
+#[test]
+fn statistics_records() {
+    let mut statistics = Statistics::new();
+    for _ in 0..4 {
+        statistics.record_started(Difficulty::Hard);
+    }
+    statistics.record_won(Difficulty::Hard, Duration::from_secs(300));
+    statistics.record_won(Difficulty::Hard, Duration::from_millis(200500));
+    statistics.record_abandoned(Difficulty::Hard);
+    statistics.record_won(Difficulty::Hard, Duration::from_secs(400));
+    let hard = statistics.get(Difficulty::Hard);
+    assert_eq!(hard.games_started, 4, "game::Statistics does not count started games");
+    assert_eq!(hard.games_won, 3, "game::Statistics does not count won games");
+    assert_eq!(hard.games_abandoned, 1, "game::Statistics does not count abandoned games");
+    assert_eq!(hard.best_time, Some(Duration::from_secs(200)), "game::Statistics does not keep the best time in whole seconds");
+    assert_eq!(hard.average_time(), Some(Duration::from_secs(300)), "game::Statistics does not average the won games' times");
+    assert_eq!(hard.current_streak, 1, "game::Statistics does not break the streak on an abandoned game");
+    assert_eq!(hard.best_streak, 2, "game::Statistics does not keep the best streak");
+    statistics.record_solved(Difficulty::Hard);
+    assert_eq!(statistics.get(Difficulty::Hard).current_streak, 0, "game::Statistics does not break the streak when the solver is used");
+    assert_eq!(*statistics.get(Difficulty::Easy), DifficultyStatistics::new(), "game::Statistics records games of another difficulty");
+}
+
+#[test]
+fn statistics_marshal_unmarshal_inverse_check() {
+    let mut statistics = Statistics::new();
+    statistics.record_started(Difficulty::Medium);
+    statistics.record_won(Difficulty::Medium, Duration::from_secs(754));
+    statistics.record_started(Difficulty::VeryHard);
+    statistics.record_solved(Difficulty::VeryHard);
+    let parsed = Statistics::parse(statistics.to_string().as_str());
+    assert_eq!(parsed, Some(statistics), "marshalling and unmarshalling of game::Statistics are not inverse operations");
+    assert_eq!(Statistics::parse("1,2,3"), None, "game::Statistics::parse() accepts malformed statistics");
+}
+
+#[test]
+fn saved_game_progress() {
+    let board_original = SudokuBoard::new("120000000000000000000000000000000000000000000000000000000000000000000000000000000");
+    let mut board_playing = board_original;
+    let mut saved_game = SavedGame{
+        board_original,
+        board_playing,
+        title: String::from("Easy game #1"),
+        notes: BoardNotes::new(),
+        history: MoveHistory::new(),
+        elapsed: Duration::from_secs(0),
+        difficulty: Some(Difficulty::Easy),
+        seed: None,
+        notes_mode: false
+    };
+    assert_eq!(saved_game.progress(), 0.0, "game::SavedGame::progress() counts the given cells");
+    board_playing[8][8] = 5;
+    saved_game.board_playing = board_playing;
+    assert_eq!(saved_game.progress(), 1.0 / 79.0, "game::SavedGame::progress() does not count the filled in cells");
+}
+
+#[test]
+fn slot_name_is_safe_file_name() {
+    assert_eq!(slot_name_for("Very hard game #12, seed 42"), "Very_hard_game__12__seed_42", "game::slot_name_for() keeps characters that are unsafe in file names");
+    assert_eq!(slot_name_for("../saves"), "___saves", "game::slot_name_for() allows leaving the saves directory");
+}
+
+fn saved_game_test_game() -> SavedGame {
+    let board_original = SudokuBoard::new("120000000000000000000000000000000000000000000000000000000000000000000000000000000");
+    let mut board_playing = board_original;
+    board_playing[4][4] = 7;
+    let mut notes = BoardNotes::new();
+    notes.toggle(0, 2, 3);
+    let mut history = MoveHistory::new();
+    history.push(Move{ row: 4, col: 4, old_value: 0, new_value: 7, old_notes: 0, new_notes: 0 });
+    SavedGame{
+        board_original,
+        board_playing,
+        title: String::from("Hard generated game, seed 42"),
+        notes,
+        history,
+        elapsed: Duration::from_secs(754),
+        difficulty: Some(Difficulty::Hard),
+        seed: Some(42),
+        notes_mode: true
+    }
+}
+
+fn assert_saved_games_equal(saved_game_1 : &SavedGame, saved_game_2 : &SavedGame, message : &str) {
+    assert!(saved_game_1.board_original == saved_game_2.board_original && saved_game_1.board_playing == saved_game_2.board_playing
+        && saved_game_1.title == saved_game_2.title && saved_game_1.notes == saved_game_2.notes && saved_game_1.history == saved_game_2.history
+        && saved_game_1.elapsed == saved_game_2.elapsed && saved_game_1.difficulty == saved_game_2.difficulty
+        && saved_game_1.seed == saved_game_2.seed && saved_game_1.notes_mode == saved_game_2.notes_mode, "{}", message);
+}
+
+#[test]
+fn saved_game_marshal_unmarshal_inverse_check() {
+    let saved_game = saved_game_test_game();
+    let parsed = SavedGame::parse(saved_game.to_string().as_str()).expect("game::SavedGame::parse() does not read what to_string() writes");
+    assert_saved_games_equal(&saved_game, &parsed, "marshalling and unmarshalling of game::SavedGame are not inverse operations");
+}
+
+#[test]
+fn saved_game_parse_line_based() {
+    let mut saved_game = saved_game_test_game();
+    let three_lines = format!("{}\n{}\n{}\n", saved_game.board_original, saved_game.board_playing, saved_game.title);
+    let parsed = SavedGame::parse(three_lines.as_str()).expect("game::SavedGame::parse() does not read three-line save files");
+    assert!(parsed.board_playing == saved_game.board_playing && parsed.title == saved_game.title && parsed.notes.is_empty() && !parsed.history.can_undo(),
+        "game::SavedGame::parse() does not read three-line save files correctly");
+
+    let all_lines = format!("{}{}\n{}\n{}\n2", three_lines, saved_game.notes, saved_game.history, saved_game.elapsed.as_secs());
+    let parsed = SavedGame::parse(all_lines.as_str()).expect("game::SavedGame::parse() does not read line-based save files");
+    saved_game.seed = None;
+    saved_game.notes_mode = false;
+    assert_saved_games_equal(&saved_game, &parsed, "game::SavedGame::parse() does not read line-based save files correctly");
+}
+
+#[test]
+fn legacy_saved_game_migration() {
+    use super::save_slots::migrate_saved_game;
+    use std::fs;
+    let mut dir = ::std::env::temp_dir();
+    dir.push(format!("sudoku_legacy_migration_{}", ::std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let legacy_path = dir.join("saved_game.sud");
+    let saved_game = saved_game_test_game();
+    fs::write(&legacy_path, format!("{}\n{}\n{}\n", saved_game.board_original, saved_game.board_playing, saved_game.title)).unwrap();
+
+    // a file stands where the saves directory would be created, so the slot can not be written
+    let blocked_dir = dir.join("blocked");
+    fs::write(&blocked_dir, "").unwrap();
+    assert!(!migrate_saved_game(&legacy_path, &blocked_dir), "game::save_slots::migrate_saved_game() reports a slot that could not be written as migrated");
+    assert!(legacy_path.is_file(), "game::save_slots::migrate_saved_game() deletes the old save when the slot could not be written");
+
+    let saves_dir = dir.join("saves");
+    assert!(migrate_saved_game(&legacy_path, &saves_dir), "game::save_slots::migrate_saved_game() does not migrate the old save");
+    assert!(!legacy_path.exists(), "game::save_slots::migrate_saved_game() keeps the old save once it has been migrated");
+    let slot = fs::read_to_string(saves_dir.join(format!("{}.sud", slot_name_for(saved_game.title.as_str())))).unwrap();
+    let migrated = SavedGame::parse(slot.as_str()).expect("game::save_slots::migrate_saved_game() writes a slot that can not be read");
+    assert!(migrated.board_playing == saved_game.board_playing && migrated.title == saved_game.title, "game::save_slots::migrate_saved_game() does not keep the game");
+
+    // an old save that an earlier attempt has already written to its slot is only removed
+    fs::write(&legacy_path, format!("{}\n{}\n{}\n", saved_game.board_original, saved_game.board_playing, saved_game.title)).unwrap();
+    assert!(migrate_saved_game(&legacy_path, &saves_dir) && !legacy_path.exists(), "game::save_slots::migrate_saved_game() does not remove an old save that is already in its slot");
+    assert_eq!(fs::read_dir(&saves_dir).unwrap().count(), 1, "game::save_slots::migrate_saved_game() copies an old save that is already in its slot");
+
+    // a slot by the same name holding another game is left alone, the old save gets a numbered slot
+    fs::write(&legacy_path, format!("{}\n{}\n{}\n", saved_game.board_original, saved_game.board_original, saved_game.title)).unwrap();
+    assert!(migrate_saved_game(&legacy_path, &saves_dir), "game::save_slots::migrate_saved_game() does not migrate an old save whose slot is taken");
+    assert_eq!(fs::read_to_string(saves_dir.join(format!("{}.sud", slot_name_for(saved_game.title.as_str())))).unwrap(), slot,
+        "game::save_slots::migrate_saved_game() overwrites an existing slot");
+    let numbered = fs::read_to_string(saves_dir.join(format!("{}_2.sud", slot_name_for(saved_game.title.as_str())))).unwrap();
+    assert_eq!(SavedGame::parse(numbered.as_str()).unwrap().board_playing, saved_game.board_original, "game::save_slots::migrate_saved_game() does not keep the old save in a numbered slot");
+
+    fs::write(&legacy_path, "not a saved game").unwrap();
+    assert!(!migrate_saved_game(&legacy_path, &saves_dir) && legacy_path.is_file(), "game::save_slots::migrate_saved_game() deletes an old save it can not read");
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn saved_game_parse_extensions() {
+    let saved_game = saved_game_test_game();
+    let extended = format!("{}[game]\nrating=2.6\n[solver]\nsteps=12\n", saved_game);
+    let parsed = SavedGame::parse(extended.as_str()).expect("game::SavedGame::parse() does not ignore unknown keys");
+    assert_saved_games_equal(&saved_game, &parsed, "game::SavedGame::parse() is confused by unknown keys");
+}
+
+#[test]
+fn saved_game_parse_invalid() {
+    let saved_game_string = saved_game_test_game().to_string();
+    assert_eq!(SavedGame::parse(saved_game_string.replace("version=1", "version=2").as_str()).err(), Some(SaveFileError::UnsupportedVersion(2)),
+        "game::SavedGame::parse() accepts save files of newer format versions");
+    assert_eq!(SavedGame::parse(saved_game_string.replace("title=", "name=").as_str()).err(), Some(SaveFileError::MissingKey(String::from("game.title"))),
+        "game::SavedGame::parse() accepts save files without a title");
+    assert_eq!(SavedGame::parse(saved_game_string.replace("difficulty=hard", "difficulty=harder").as_str()).err(), Some(SaveFileError::InvalidValue(String::from("game.difficulty"))),
+        "game::SavedGame::parse() accepts invalid difficulties");
+    assert_eq!(SavedGame::parse("not a saved game").err(), Some(SaveFileError::UnknownFormat), "game::SavedGame::parse() accepts arbitrary text");
+}
+
+#[test]
+fn settings_marshal_unmarshal_inverse_check() {
+    let mut settings = Settings::new();
+    settings.game_source = GameSource::Database;
+    assert_eq!(Settings::parse(settings.to_string().as_str()), settings, "marshalling and unmarshalling of game::Settings are not inverse operations");
+    assert_eq!(Settings::parse("game_source=elsewhere\nvolume=3"), Settings::new(), "game::Settings::parse() does not ignore unknown settings");
+}
+
+#[test]
+fn board_parse_notations() {
+    let digits = "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+    let board : SudokuBoard = digits.parse().expect("game::SudokuBoard::from_str() refuses a valid board");
+    let dots : SudokuBoard = digits.replace('0', ".").parse().expect("game::SudokuBoard::from_str() refuses dots for empty cells");
+    assert_eq!(board, dots, "game::SudokuBoard::from_str() reads dots differently from zeros");
+    let grid = "5 3 . | . 7 . | . . .\n6 . . | 1 9 5 | . . .\n. 9 8 | . . . | . 6 .\n------+-------+------\n\
+        8 . . | . 6 . | . . 3\n4 . . | 8 . 3 | . . 1\n7 . . | . 2 . | . . 6\n------+-------+------\n\
+        . 6 . | . . . | 2 8 .\n. . . | 4 1 9 | . . 5\n. . . | . 8 . | . 7 9\n";
+    assert_eq!(grid.parse::<SudokuBoard>(), Ok(board), "game::SudokuBoard::from_str() does not skip whitespace and grid separators");
+    assert_eq!(board.to_string(), digits, "game::SudokuBoard::from_str() and to_string() are not inverse operations");
+}
+
+#[test]
+fn board_parse_errors() {
+    assert_eq!("12345".parse::<SudokuBoard>(), Err(ParseBoardError::WrongLength(5)), "game::SudokuBoard::from_str() accepts a short board");
+    assert_eq!("0".repeat(82).parse::<SudokuBoard>(), Err(ParseBoardError::WrongLength(82)), "game::SudokuBoard::from_str() accepts a long board");
+    let with_letter = format!("{}a{}", "0".repeat(10), "0".repeat(70));
+    assert_eq!(with_letter.parse::<SudokuBoard>(), Err(ParseBoardError::InvalidCharacter{ position: 10, character: 'a' }),
+        "game::SudokuBoard::from_str() does not report the invalid character");
+    let conflicting = format!("{}1{}1{}", "0".repeat(2), "0".repeat(16), "0".repeat(61));
+    assert_eq!(conflicting.parse::<SudokuBoard>(), Err(ParseBoardError::ConflictingGivens{ value: 1, first: (0, 2), second: (2, 1) }),
+        "game::SudokuBoard::from_str() does not report givens conflicting in a box");
+    assert!(SudokuBoard::parse_cells(conflicting.as_str()).is_ok(), "game::SudokuBoard::parse_cells() refuses conflicting values");
+}
+
+#[test]
+fn export_formats_import_back() {
+    let givens = SudokuBoard::new("530070000600195000098000060800060003400803001700020006060000280000419005000080079");
+    let mut progress = givens;
+    progress[0][2] = 4;
+    let solution = solve(&givens).unwrap();
+    let import_formats = [(ExportFormat::Sdm, ImportFormat::Sdm), (ExportFormat::OpenSudoku, ImportFormat::OpenSudoku)];
+    for (export_format, import_format) in import_formats.iter() {
+        let exported = export_game(*export_format, "Easy game #1", &givens, Some(&progress), true);
+        let parsed = parse_puzzles(exported.as_str(), *import_format);
+        assert!(parsed.invalid.is_empty(), "game::export_game() writes {:?} that can not be imported", export_format);
+        assert!(parsed.puzzles.len() == 1 && parsed.puzzles[0].1 == givens, "game::export_game() writes {:?} that does not import as just its givens", export_format);
+    }
+    let open_sudoku = export_game(ExportFormat::OpenSudoku, "Easy game #1", &givens, Some(&progress), true);
+    assert!(open_sudoku.contains(format!("<!-- Progress: {} -->", progress).as_str()), "game::export_game() leaves the progress out of OpenSudoku files");
+    assert!(open_sudoku.contains(format!("<!-- Solution: {} -->", solution).as_str()), "game::export_game() leaves the solution out of OpenSudoku files");
+    let ss = export_game(ExportFormat::Ss, "Easy game #1", &givens, Some(&progress), true);
+    let puzzles = parse_puzzles(ss.as_str(), ImportFormat::Ss).puzzles;
+    assert!(puzzles.len() == 1 && puzzles[0].1 == givens, "game::export_game() writes a .ss file that does not import as its givens");
+    assert!(ss.contains("# Solution\n# 534|678|912\n"), "game::export_game() leaves the solution out of .ss files");
+
+    let ascii = export_game(ExportFormat::Ascii, "Easy game #1", &givens, Some(&givens), false);
+    assert!(ascii.starts_with("Easy game #1\n\nPuzzle\n+-------+-------+-------+\n| 5 3 . | . 7 . | . . . |\n"), "game::export_game() does not draw an ASCII grid");
+    assert!(!ascii.contains("Progress") && !ascii.contains("Solution"), "game::export_game() exports progress that was not made or a solution that was not asked for");
+    let grid = &ascii[ascii.find('+').unwrap()..];
+    assert_eq!(SudokuBoard::parse_cells(grid), Ok(givens), "game::export_game() draws an ASCII grid that can not be read back");
+}
+
+#[test]
+fn svg_rendering() {
+    let givens = SudokuBoard::new("530070000600195000098000060800060003400803001700020006060000280000419005000080079");
+    let mut entries = givens;
+    entries[0][2] = 4;
+    let svg = board_svg(&givens, Some(&entries));
+    assert!(svg.starts_with("<?xml") && svg.ends_with("</svg>\n"), "game::board_svg() does not produce an SVG document");
+    assert_eq!(svg.matches("<text").count(), 31, "game::board_svg() does not draw every given and entry");
+    assert_eq!(svg.matches("fill=\"#7777dd\">4<").count(), 1, "game::board_svg() does not draw entries in their own colour");
+    assert_eq!(svg.matches("<line").count(), 20, "game::board_svg() does not draw the grid lines");
+    assert_eq!(board_svg(&givens, None).matches("<text").count(), 30, "game::board_svg() draws entries that were not asked for");
+
+    let puzzles : Vec<PrintedPuzzle> = (0..7).map(|index| PrintedPuzzle{
+        title: format!("Easy game #{} <printed>", index + 1),
+        seed: Some(index),
+        board: givens
+    }).collect();
+    let pages = puzzle_pages_svg(&puzzles);
+    assert_eq!(pages.len(), 2, "game::puzzle_pages_svg() does not put six puzzles on a page");
+    assert!(pages[1].contains("Easy game #7 &lt;printed&gt;") && pages[1].contains("Seed: 6"), "game::puzzle_pages_svg() does not label the puzzles");
+    let solution_pages = solution_pages_svg(&puzzles);
+    assert_eq!(solution_pages.len(), 1, "game::solution_pages_svg() does not put twelve solutions on a page");
+    assert_eq!(solution_pages[0].matches("fill=\"#7777dd\"").count(), 7 * 51, "game::solution_pages_svg() does not fill the solutions in");
+}
+
+fn session_test_game() -> GameSession {
+    let board = SudokuBoard::new("530070000600195000098000060800060003400803001700020006060000280000419005000080079");
+    // without a difficulty the session leaves the statistics, and so the statistics file, alone
+    GameSession::from_saved_game(SavedGame{
+        board_original: board,
+        board_playing: board,
+        title: String::from("Easy game #1, seed 1"),
+        notes: BoardNotes::new(),
+        history: MoveHistory::new(),
+        elapsed: Duration::from_secs(0),
+        difficulty: None,
+        seed: None,
+        notes_mode: false
+    }, String::from("Easy_game__1__seed_1"))
+}
+
+#[test]
+fn session_moves_and_undo() {
+    let mut session = session_test_game();
+    assert!(!session.needs_saving(), "game::GameSession needs saving right after it has been loaded");
+    session.place(0, 2, 4);
+    assert_eq!(session.board_playing[0][2], 4, "game::GameSession::place() does not enter the value");
+    assert!(session.needs_saving(), "game::GameSession::place() does not mark the game for saving");
+    assert!(session.undo() && session.board_playing[0][2] == 0, "game::GameSession::undo() does not revert the move");
+    assert!(session.redo() && session.board_playing[0][2] == 4, "game::GameSession::redo() does not make the move again");
+    session.toggle_pause();
+    assert!(session.is_paused() && !session.is_timer_running(), "game::GameSession::toggle_pause() does not stop the timer");
+    session.toggle_pause();
+    session.reset();
+    assert_eq!(session.board_playing, session.board_original, "game::GameSession::reset() does not clear the entries");
+    assert!(!session.undo(), "game::GameSession::reset() keeps the history");
+}
+
+#[test]
+fn session_solves_in_background() {
+    let mut session = session_test_game();
+    let mut statistics = Statistics::new();
+    session.place(0, 2, 1);
+    session.start_solving();
+    assert!(session.is_solving(), "game::GameSession::start_solving() does not start the solver");
+    session.cancel_solving();
+    assert!(!session.is_solving() && session.board_playing[0][2] == 1, "game::GameSession::cancel_solving() does not leave the board as it was");
+    session.start_solving();
+    for _ in 0..500 {
+        session.update(&mut statistics);
+        if !session.is_solving() {
+            break;
+        }
+        ::std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(!session.is_solving(), "game::GameSession::update() does not take in the solver's outcome");
+    assert_eq!(Some(session.board_playing), solve(&session.board_original), "game::GameSession::update() does not fill the solution in");
+    assert_eq!(session.solver_feedback(), Some(&SolverFeedback::ContradictingEntries(vec![(0, 2)])), "game::GameSession does not point out the entries that contradict the solution");
+    assert!(session.is_finished() && !session.is_timer_running(), "game::GameSession::update() does not stop the timer of a finished game");
+    assert!(!session.is_won(), "game::GameSession counts a game finished by the solver as won");
+    assert_eq!(statistics, Statistics::new(), "game::GameSession records a game of unknown difficulty");
+    let solution = session.board_playing;
+    let mut session = session_test_game();
+    for (i, j) in (0..9).flat_map(|i| (0..9).map(move |j| (i, j))) {
+        session.place(i, j, solution[i][j]);
+    }
+    session.update(&mut statistics);
+    assert!(session.is_won() && !session.is_timer_running(), "game::GameSession::update() does not notice that the player has won");
+}
+
+#[test]
+fn session_explains_unsolvable_boards() {
+    let mut statistics = Statistics::new();
+    let mut session = session_test_game();
+    session.board_original = SudokuBoard::new("516849732307605000809700065135060907472591006968370050253186074684207500791050608");
+    session.board_playing = session.board_original;
+    session.start_solving();
+    session.wait_for_solver(&mut statistics);
+    match session.solver_feedback() {
+        Some(&SolverFeedback::NoSolution(_)) => {},
+        feedback => panic!("game::GameSession does not tell that the board has no solution, got {:?}", feedback)
+    }
+    assert_eq!(session.board_playing, session.board_original, "game::GameSession changes a board that has no solution");
+    session.board_original[0][1] = 5;
+    session.board_playing = session.board_original;
+    session.start_solving();
+    session.wait_for_solver(&mut statistics);
+    assert_eq!(session.solver_feedback(), Some(&SolverFeedback::NoSolution(vec![(0, 0), (0, 1), (6, 1)])), "game::GameSession does not point out conflicting givens");
+    session.board_original = SudokuBoard::new(&"0".repeat(81));
+    session.board_playing = session.board_original;
+    session.start_solving();
+    session.wait_for_solver(&mut statistics);
+    match session.solver_feedback() {
+        Some(SolverFeedback::MultipleSolutions(cells)) => assert!(!cells.is_empty(), "game::GameSession does not point out where the solutions differ"),
+        feedback => panic!("game::GameSession does not tell that the board has multiple solutions, got {:?}", feedback)
+    }
+    assert!(session.is_finished(), "game::GameSession does not fill in one of the solutions");
+    session.undo();
+    assert!(session.solver_feedback().is_some(), "game::GameSession::undo() clears the feedback without a change");
+    session.place(0, 0, 0);
+    assert!(session.solver_feedback().is_none(), "game::GameSession does not clear the feedback once the board changes");
+}
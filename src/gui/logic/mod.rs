@@ -1,145 +1,314 @@
 mod sudoku_board;
 
-use ::game::{self, SudokuBoard, Difficulty};
-
-/// Handles all state
-struct State {
-    in_game: bool,
-	board_original: Option<SudokuBoard>,
-	board_playing: Option<SudokuBoard>,
-	game_title: Option<String>,
-	is_solving: bool,
-	board_solving: Option<Option<SudokuBoard>>
+use ::std::time::Duration;
+use ::game::{self, SudokuBoard, Difficulty, GameSource, GameSession, Database, Statistics};
+
+/// A hint shown to the player, it only applies to the board as it was when the hint was requested
+struct Hint {
+	board: SudokuBoard,
+	message: String,
+	placement: Option<(usize, usize, u8)>,
+	cells: Vec<(usize, usize)>
 }
 
-static mut STATE : Option<State> = Option::None;
-
-/// Initialize game state
-pub fn initialize() {
-    unsafe {
-		// load saved game if there is one
-		if let Some((board_original, board_playing, game_title)) = game::load_from_file() {
-			game::remove_saved_game();	// consuming the save file
-			STATE = Some(State{
-				in_game: true,
-				board_original: Some(board_original),
-				board_playing: Some(board_playing),
-				game_title: Some(game_title),
-				is_solving: false,
-				board_solving: None
-			});
-		} else {
-			STATE = Some(State{
-				in_game: false,
-				board_original: None,
-				board_playing: None,
-				game_title: None,
-				is_solving: false,
-				board_solving: None
-			});
-		}
-    }
+/// The screens shown when not in a game
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Screen {
+	Title,
+	Statistics,
+	LoadGame
 }
 
-pub fn check_initialized(error_message : &'static str) {
-	unsafe {
-		assert!(STATE.is_some(), error_message);
+/// Handles all state, it is owned by the event loop and passed to draw_ui
+pub struct State {
+	screen: Screen,
+	database: Database,
+	statistics: Statistics,
+	// the game being played, there is none while the title, statistics or load game screen is shown
+	session: Option<GameSession>,
+	// the saved games listed on the load game screen and the one the player has picked
+	save_slots: Vec<game::SaveSlot>,
+	selected_slot: Option<usize>,
+	load_error: Option<String>,
+	game_source: GameSource,
+	seed_text: String,
+	hint: Option<Hint>,
+	// where the game was exported to or why it could not be, or why the solver has not solved the puzzle, shown until the board changes
+	status_message: Option<String>
+}
+
+/// Initialize game state, new games are picked from the given database when the player asks for predefined ones
+pub fn initialize(database : Database) -> State {
+	State{
+		screen: Screen::Title,
+		database,
+		statistics: game::load_statistics(),
+		session: None,
+		save_slots: Vec::new(),
+		selected_slot: None,
+		load_error: None,
+		game_source: game::load_settings().game_source,
+		seed_text: String::new(),
+		hint: None,
+		status_message: None
 	}
 }
 
-fn start_new_game(difficulty : Difficulty) {
-    unsafe {
-        if let Some(ref mut state) = STATE {
-			let difficulty_string = match difficulty {
-				Difficulty::Easy => "Easy",
-				Difficulty::Medium => "Medium",
-				Difficulty::Hard => "Hard",
-				Difficulty::VeryHard => "Very hard"
-			};
-			let (board_original, game_index) = game::generate_game(difficulty);
-			let board_playing = board_original.clone();
-			
-            state.in_game = true;
-			state.board_original = Some(board_original);
-			state.board_playing = Some(board_playing);
-			state.game_title = Some(format!("{} game #{}", difficulty_string, game_index + 1));
-			state.is_solving = false;
-			state.board_solving = None;
-        } else {
-            panic!("game::make_new_game() called with STATE being None");
-        }
-    }
+fn start_new_game(state : &mut State, difficulty : Difficulty) {
+	// the player may type in a seed to replay a game, otherwise a random one is used
+	let seed = match state.seed_text.parse::<u64>() {
+		Ok(seed) => seed,
+		Err(_) => game::random_seed()
+	};
+	state.session = GameSession::new(difficulty, state.game_source, seed, Some(&state.database));
+	state.statistics.record_started(difficulty);
+	game::save_statistics(&state.statistics);
+	state.hint = None;
+	state.status_message = None;
 }
 
-fn reset_game() {
-    unsafe {
-        if let Some(ref mut state) = STATE {
-			state.is_solving = false;
-			state.board_playing = Some(state.board_original.unwrap().clone());
-		} else {
-            panic!("game::reset_game() called with STATE being None");
-        }
+fn reset_game(state : &mut State) {
+	if let Some(ref mut session) = state.session {
+		session.reset();
 	}
+	state.hint = None;
+	state.status_message = None;
 }
 
-fn solve_game() {
-	use ::std::thread;
-	
-    unsafe {
-        if let Some(ref mut state) = STATE {
-			reset_game();
-			
-			// given that we have reset, board_playing = board_original
-			state.is_solving = true;
-			state.board_solving = None;
-			
-			let board = state.board_playing.unwrap().clone();
-			let is_solving : &bool = &state.is_solving;
-			let board_option : &mut Option<Option<SudokuBoard>> = &mut state.board_solving;
-			
-			// running the solver in a new thread so as not to block the UI, it takes a bit of time for Very Hard games
-			thread::spawn(move || {
-				let board_solution = ::game::solve(&board);
-				
-				// only if solving has not been interrupted do the assignment
-				if *is_solving {
-					*board_option = Some(board_solution);
+fn solve_game(state : &mut State) {
+	if let Some(ref mut session) = state.session {
+		session.start_solving();
+	}
+	state.hint = None;
+	state.status_message = None;
+}
+
+fn cancel_solving(state : &mut State) {
+	if let Some(ref mut session) = state.session {
+		session.cancel_solving();
+	}
+}
+
+fn show_hint(state : &mut State) {
+	let session = match state.session {
+		Some(ref mut session) => session,
+		None => return
+	};
+	let board_playing = session.board_playing;
+	state.status_message = None;
+	// pressing Hint again while the hint still applies fills the cell in
+	if let Some(hint) = state.hint.take() {
+		if hint.board == board_playing {
+			if let Some((i, j, value)) = hint.placement {
+				session.place(i, j, value);
+				return;
+			}
+		}
+	}
+	state.hint = Some(find_hint(&board_playing));
+}
+
+/// Explains the next logical step from the given board
+fn find_hint(board : &SudokuBoard) -> Hint {
+	let mut hint = Hint{
+		board: board.clone(),
+		message: String::new(),
+		placement: None,
+		cells: Vec::new()
+	};
+	if !game::check_for_errors(board).is_empty() {
+		hint.message = String::from("Fix the highlighted mistakes first.");
+	} else if game::solve(board).is_none() {
+		hint.message = String::from("Some of your entries are wrong, the puzzle can not be solved from here.");
+	} else if let Some(steps) = game::next_placement(board) {
+		let (placement_step, preceding_steps) = steps.split_last().unwrap();
+		let mut preceding_techniques : Vec<&str> = preceding_steps.iter().map(|step| step.technique.name()).collect();
+		preceding_techniques.dedup();
+		if !preceding_techniques.is_empty() {
+			hint.message = format!("After eliminating candidates ({}), ", preceding_techniques.join(", "));
+		}
+		hint.message.push_str(format!("{}: {}. Press Hint again to fill it in.", placement_step.technique.name(), placement_step.explanation).as_str());
+		hint.placement = placement_step.placement;
+		for step in steps.iter() {
+			for cell in step.cells.iter() {
+				if !hint.cells.contains(cell) {
+					hint.cells.push(*cell);
 				}
-			});
-		} else {
-            panic!("game::solve_game() called with STATE being None");
-        }
+			}
+		}
+	} else {
+		hint.message = String::from("No further step can be found with the techniques known to the hint system.");
+	}
+	hint
+}
+
+/// Reverts the player's last move
+/// Not possible while the board is being solved
+pub fn undo_move(state : &mut State) {
+	if let Some(ref mut session) = state.session {
+		if !session.is_solving() && !session.is_paused() {
+			state.status_message = None;
+			session.undo();
+		}
+	}
+}
+
+/// Makes the player's last undone move again
+/// Not possible while the board is being solved
+pub fn redo_move(state : &mut State) {
+	if let Some(ref mut session) = state.session {
+		if !session.is_solving() && !session.is_paused() {
+			state.status_message = None;
+			session.redo();
+		}
+	}
+}
+
+/// The timer and the solver's progress are displayed, so while either runs the UI has to be redrawn even without any input
+pub fn needs_redrawing(state : &State) -> bool {
+	state.session.as_ref().map_or(false, |session| session.is_timer_running() || session.is_solving())
+}
+
+fn show_screen(state : &mut State, screen : Screen) {
+	// the saved games are listed anew every time the load game screen is opened
+	if screen == Screen::LoadGame {
+		state.save_slots = game::list_save_slots();
+		state.selected_slot = None;
+		state.load_error = None;
+	}
+	state.screen = screen;
+}
+
+fn select_slot(state : &mut State, index : usize) {
+	state.selected_slot = Some(index);
+	state.load_error = None;
+}
+
+/// Continues the saved game the player has picked on the load game screen
+fn load_selected_slot(state : &mut State) {
+	if let Some(index) = state.selected_slot {
+		// the game is read again in case the slot has changed since it was listed
+		match game::load_from_slot(state.save_slots[index].name.as_str()) {
+			Ok(saved_game) => {
+				state.session = Some(GameSession::from_saved_game(saved_game, state.save_slots[index].name.clone()));
+				state.screen = Screen::Title;
+				state.save_slots = Vec::new();
+				state.selected_slot = None;
+				state.load_error = None;
+				state.hint = None;
+				state.status_message = None;
+			},
+			Err(error) => state.load_error = Some(format!("The game can not be loaded: {}.", error))
+		}
 	}
 }
 
-fn quit_game() {
-    unsafe {
-        if let Some(ref mut state) = STATE {
-            state.in_game = false;
-			state.board_original = None;
-			state.board_playing = None;
-			state.game_title = None;
-			state.is_solving = false;
-			state.board_solving = None;
-        } else {
-            panic!("game::quit_game() called with STATE being None");
-        }
-    }
+fn delete_selected_slot(state : &mut State) {
+	state.load_error = None;
+	if let Some(index) = state.selected_slot.take() {
+		game::delete_save_slot(state.save_slots[index].name.as_str());
+		state.save_slots.remove(index);
+	}
+}
+
+/// Pausing stops the timer and hides the board until the game is resumed
+fn toggle_pause(state : &mut State) {
+	if let Some(ref mut session) = state.session {
+		session.toggle_pause();
+	}
+}
+
+fn toggle_notes_mode(state : &mut State) {
+	if let Some(ref mut session) = state.session {
+		session.toggle_notes_mode();
+	}
+}
+
+fn toggle_game_source(state : &mut State) {
+	state.game_source = match state.game_source {
+		GameSource::Generator => GameSource::Database,
+		GameSource::Database => GameSource::Collection,
+		GameSource::Collection => GameSource::Generator
+	};
+	game::save_settings(&game::Settings{
+		game_source: state.game_source
+	});
+}
+
+fn set_seed_text(state : &mut State, text : &str) {
+	// seeds are unsigned 64-bit numbers, which have at most 20 digits
+	state.seed_text = text.chars().filter(|c| c.is_digit(10)).take(20).collect();
 }
 
-pub fn on_exit_event() {
-	unsafe {
-		if let Some(ref mut state) = STATE {
-			// if in-game, saving it to hard disk
-			if state.in_game {
-				game::save_to_file(state.board_original.as_ref().unwrap(), state.board_playing.as_ref().unwrap(), state.game_title.as_ref().unwrap().as_str());
+/// Saves the game and goes back to the title screen, it can be continued from the load game screen
+fn return_to_menu(state : &mut State) {
+	if let Some(ref mut session) = state.session {
+		session.cancel_solving();
+		// the game stays open if it can not be saved, so that it is not lost
+		if let Err(error) = session.save() {
+			state.status_message = Some(format!("The game can not be saved: {}.", error));
+			return;
+		}
+	}
+	state.session = None;
+	state.hint = None;
+	state.status_message = None;
+}
+
+fn quit_game(state : &mut State) {
+	if let Some(session) = state.session.take() {
+		session.abandon(&mut state.statistics);
+	}
+	state.hint = None;
+	state.status_message = None;
+}
+
+/// Exports the game in every format to the exports directory, the solution is only included once the game is finished
+fn export_current_game(state : &mut State) {
+	let session = match state.session {
+		Some(ref session) => session,
+		None => return
+	};
+	let include_solution = session.is_finished();
+	let name = game::slot_name_for(session.title.as_str());
+	let mut exported_path = None;
+	for format in game::ExportFormat::ALL.iter() {
+		let contents = game::export_game(*format, session.title.as_str(), &session.board_original, Some(&session.board_playing), include_solution);
+		match game::save_export(name.as_str(), *format, contents.as_str()) {
+			Ok(path) => exported_path = Some(path),
+			Err(error) => {
+				state.status_message = Some(format!("The game can not be exported: {}.", error));
+				return;
 			}
-		} else {
-            panic!("game::on_exit_event() called with STATE being None");
-        }
+		}
+	}
+	if let Some(directory) = exported_path.as_ref().and_then(|path| path.parent()) {
+		state.status_message = Some(format!("Exported as {}.txt, .sdm (the puzzle only), .ss and .opensudoku to {}", name, directory.display()));
 	}
 }
 
+pub fn on_exit_event(state : &mut State) {
+	if let Some(ref mut session) = state.session {
+		// there is no one left to tell if this fails, the game is closing
+		let _ = session.save();
+	}
+}
+
+/// Describes the player's statistics for each difficulty, a paragraph each
+fn statistics_text(statistics : &Statistics) -> String {
+	use ::format::format_duration;
+	let format_time = |time : Option<Duration>| time.map_or(String::from("-"), format_duration);
+	let paragraphs : Vec<String> = Difficulty::ALL.iter().map(|difficulty| {
+		let difficulty_statistics = statistics.get(*difficulty);
+		format!("{}\nStarted: {}   Won: {}   Quit: {}   Solver used: {}\nBest time: {}   Average time: {}\nWin streak: {}   Best win streak: {}",
+			difficulty.name(),
+			difficulty_statistics.games_started, difficulty_statistics.games_won, difficulty_statistics.games_abandoned, difficulty_statistics.games_solved,
+			format_time(difficulty_statistics.best_time), format_time(difficulty_statistics.average_time()),
+			difficulty_statistics.current_streak, difficulty_statistics.best_streak)
+	}).collect();
+	paragraphs.join("\n\n")
+}
+
 use conrod::{self, widget, Colorable, Positionable, Sizeable, Labelable, Widget};
 
 widget_ids!(
@@ -151,12 +320,38 @@ widget_ids!(
 		text_button_alignment,
 		button_hard,
 		button_very_hard,
+		button_game_source,
+		text_seed_label,
+		text_box_seed,
 		text_game_title,
+		button_pause,
+		button_export,
+		button_menu,
+		rectangle_paused,
+		text_paused,
 		text_game_status,
 		sudoku_board,
 		button_reset,
 		button_solve,
-		button_quit_game
+		button_quit_game,
+		text_game_button_alignment,
+		button_undo,
+		button_redo,
+		button_notes,
+		button_hint,
+		button_load_game,
+		button_statistics,
+		text_statistics_title,
+		text_statistics,
+		button_statistics_back,
+		text_load_title,
+		text_no_save_slots,
+		list_save_slots,
+		text_load_error,
+		text_load_button_alignment,
+		button_load_slot,
+		button_delete_slot,
+		button_load_back
 	}
 );
 
@@ -165,192 +360,503 @@ pub fn generate_widget_ids(ui : &mut conrod::Ui) -> Ids {
 	Ids::new(ui.widget_id_generator())
 }
 
-pub fn draw_ui(ui : &mut conrod::UiCell, ids : &Ids) {
-	unsafe {
-		if let Some(ref mut state) = STATE {
-			// calculating paddings based on window size
-			let padding_vertical_bigger = 0.0175 * ui.win_h;
-			let padding_vertical_smaller = padding_vertical_bigger / 2.0;
-			let padding_vertical_biggest = padding_vertical_smaller + padding_vertical_bigger;
-							
-			// ugly, is there a better way?
-			widget::Text::new("")
-				.mid_top()
-				.font_size(padding_vertical_bigger.round() as u32)
-				.set(ids.text_top_padding, ui);
-
-			if state.in_game {
-				let smaller_window_dimension = if ui.win_w < ui.win_h {
-					ui.win_w
-				} else {
-					ui.win_h
-				};
-				let board_size : f64 = 0.825 * smaller_window_dimension;
-				let mut game_button_width : f64;
-				let mut game_button_height : f64;
-				if ui.win_w < ui.win_h {
-					game_button_width = ui.win_w * 0.215;
-					game_button_height = game_button_width / 4.5;
-				} else {
-					game_button_height = ui.win_h * 0.045;
-					game_button_width = game_button_height * 4.5;
+pub fn draw_ui(ui : &mut conrod::UiCell, ids : &Ids, state : &mut State) {
+	// calculating paddings based on window size
+	let padding_vertical_bigger = 0.0175 * ui.win_h;
+	let padding_vertical_smaller = padding_vertical_bigger / 2.0;
+	let padding_vertical_biggest = padding_vertical_smaller + padding_vertical_bigger;
+					
+	// ugly, is there a better way?
+	widget::Text::new("")
+		.mid_top()
+		.font_size(padding_vertical_bigger.round() as u32)
+		.set(ids.text_top_padding, ui);
+
+	if state.session.is_some() {
+		let smaller_window_dimension = if ui.win_w < ui.win_h {
+			ui.win_w
+		} else {
+			ui.win_h
+		};
+		let board_size : f64 = 0.78 * smaller_window_dimension;
+		let mut game_button_width : f64;
+		let mut game_button_height : f64;
+		if ui.win_w < ui.win_h {
+			game_button_width = ui.win_w * 0.215;
+			game_button_height = game_button_width / 4.5;
+		} else {
+			game_button_height = ui.win_h * 0.045;
+			game_button_width = game_button_height * 4.5;
+		}
+		let game_button_font_size = (game_button_height * 0.75).round() as u32;
+		let status_text_font_size = (0.0255 * ui.win_h).round() as u32;
+
+		// what the buttons show is read from the session before any of them is clicked
+		let (game_title, is_paused, can_pause, notes_mode) = {
+			let session = state.session.as_ref().unwrap();
+			(format!("{}   {}", session.title, ::format::format_duration(session.elapsed_time())), session.is_paused(),
+				session.is_timer_running() || session.is_paused(), session.notes_mode)
+		};
+		widget::Text::new(game_title.as_str())
+			.down_from(ids.text_top_padding, 0.0)
+			.align_middle_x()
+			.color(conrod::color::BLACK)
+			.font_size(status_text_font_size)
+			.set(ids.text_game_title, ui);
+
+		// a finished game has its timer stopped for good, so there is nothing to pause
+		if can_pause {
+			for _click in widget::Button::new()
+				.right_from(ids.text_game_title, padding_vertical_smaller)
+				.w_h(game_button_width * 0.6, game_button_height * 0.8)
+				.label(if is_paused { "Resume" } else { "Pause" })
+				.label_font_size((game_button_font_size as f64 * 0.8).round() as u32)
+				.set(ids.button_pause, ui) {
+					toggle_pause(state);
 				}
-				let game_button_font_size = (game_button_height * 0.75).round() as u32;
-				let status_text_font_size = (0.0255 * ui.win_h).round() as u32;
-			
-				widget::Text::new(state.game_title.as_ref().unwrap().as_str())
-					.down_from(ids.text_top_padding, 0.0)
-					.align_middle_x()
+		}
+
+		for _click in widget::Button::new()
+			.left_from(ids.text_game_title, padding_vertical_smaller)
+			.w_h(game_button_width * 0.6, game_button_height * 0.8)
+			.label("Export")
+			.label_font_size((game_button_font_size as f64 * 0.8).round() as u32)
+			.set(ids.button_export, ui) {
+				export_current_game(state);
+			}
+
+		// a hint no longer applies once the player changes the board
+		let hint_is_stale = match state.hint {
+			Some(ref hint) => hint.board != state.session.as_ref().unwrap().board_playing,
+			None => false
+		};
+		if hint_is_stale {
+			state.hint = None;
+		}
+		let (hint_cell, hint_related_cells) = match state.hint {
+			Some(ref hint) => (hint.placement.map(|(i, j, _)| (i, j)), hint.cells.as_slice()),
+			None => (None, &[][..])
+		};
+
+		// the board is hidden while the game is paused
+		let board_area_id : widget::id::Id;
+		let mut game_is_finished = false;
+		let solver_progress : Option<String>;
+		let solver_message : Option<&'static str>;
+		{
+			let session = state.session.as_mut().unwrap();
+			// takes in the solver's outcome and stops the timer once the game has been won, before the board is drawn so that a solution shows up right away
+			session.update(&mut state.statistics);
+			solver_progress = session.solver().map(|solver| format!("Solving… {} positions tried in {} s. Press Cancel to stop.",
+				solver.nodes_explored(), solver.elapsed().as_secs()));
+			solver_message = session.solver_feedback().map(|feedback| feedback.message());
+			let marked_cells : Vec<(usize, usize)> = session.solver_feedback().map_or(Vec::new(), |feedback| feedback.cells().to_vec());
+			let is_solving = session.is_solving();
+
+			if session.is_paused() {
+				widget::Rectangle::fill_with([board_size, board_size], conrod::color::rgb(0.8, 0.8, 0.8))
+					.down_from(ids.text_game_title, padding_vertical_smaller)
+					.align_middle_x_of(ids.text_top_padding)
+					.set(ids.rectangle_paused, ui);
+				widget::Text::new("Paused")
+					.middle_of(ids.rectangle_paused)
 					.color(conrod::color::BLACK)
-					.font_size(status_text_font_size)
-					.set(ids.text_game_title, ui);
-				
-				if state.is_solving {
-					match state.board_solving {
-						Some(Some(board_solution)) => {
-							// board is solved, copy solution and reset state
-							state.board_playing = Some(board_solution);
-							state.board_solving = None;
-							state.is_solving = false;
-						},
-						Some(None) => {
-							// board is unsolveable, reset state
-							state.board_solving = None;
-							state.is_solving = false;
-						},
-						_ => ()
-					}
-				}
-				let mut game_is_finished = false;
-				for _finished in sudoku_board::SudokuBoard::new()
-					.set_board(state.board_original.as_ref().unwrap(), state.board_playing.as_mut().unwrap(), state.is_solving)
+					.font_size(status_text_font_size * 2)
+					.set(ids.text_paused, ui);
+				board_area_id = ids.rectangle_paused;
+			} else {
+				let board_event = sudoku_board::SudokuBoard::new()
+					.set_board(&session.board_original, &mut session.board_playing, is_solving)
+					.set_notes(&mut session.notes, notes_mode)
+					.highlight_cells(hint_cell, hint_related_cells)
+					.mark_cells(marked_cells.as_slice())
 					.down_from(ids.text_game_title, padding_vertical_smaller)
-					.align_middle_x()
+					.align_middle_x_of(ids.text_top_padding)
 					.w_h(board_size, board_size)
-					.set(ids.sudoku_board, ui) {
-						game_is_finished = true;
-					}
-				let game_is_finished = game_is_finished;
-				
-				let mut control_buttons_down_from : widget::id::Id;
-				let mut control_buttons_padding : f64;
-				if !game_is_finished {
-					control_buttons_down_from = ids.sudoku_board;
-					control_buttons_padding = padding_vertical_biggest;
-				} else {
-					widget::Text::new("You won. Congratulations!")
-						.down_from(ids.sudoku_board, padding_vertical_smaller)
-						.align_middle_x()
-						.color(conrod::color::BLACK)
-						.font_size(status_text_font_size)
-						.set(ids.text_game_status, ui);
-					control_buttons_down_from = ids.text_game_status;
-					control_buttons_padding = padding_vertical_bigger;
+					.set(ids.sudoku_board, ui);
+				for board_move in board_event.moves.into_iter() {
+					session.push_move(board_move);
+					state.status_message = None;
 				}
+				game_is_finished = board_event.is_finished;
+				board_area_id = ids.sudoku_board;
+			}
 
-				for _click in widget::Button::new()
-					.down_from(control_buttons_down_from, control_buttons_padding)
-					.align_middle_x()
-					.w_h(game_button_width, game_button_height)
-					.label("Solve it")
-					.label_font_size(game_button_font_size)
-					.set(ids.button_solve, ui) {
-						// do not solve the board if it has already been solved
-						if !game_is_finished {
-							solve_game();
-						}
-					}
+			// autosaving, so that a crash loses at most the last few seconds of the timer
+			if session.needs_saving() {
+				if let Err(error) = session.save() {
+					state.status_message = Some(format!("The game can not be saved: {}.", error));
+				}
+			}
+		}
+		let game_is_finished = game_is_finished;
+		let is_solving = solver_progress.is_some();
+		
+		let mut control_buttons_down_from : widget::id::Id;
+		let mut control_buttons_padding : f64;
+		let status_text = if let Some(ref status_message) = state.status_message {
+			Some(status_message.as_str())
+		} else if solver_message.is_some() {
+			solver_message
+		} else if game_is_finished {
+			Some("You won. Congratulations!")
+		} else if let Some(ref solver_progress) = solver_progress {
+			Some(solver_progress.as_str())
+		} else {
+			state.hint.as_ref().map(|hint| hint.message.as_str())
+		};
+		if let Some(status_text) = status_text {
+			widget::Text::new(status_text)
+				.down_from(board_area_id, padding_vertical_smaller)
+				.align_middle_x_of(board_area_id)
+				.w(board_size)
+				.center_justify()
+				.wrap_by_word()
+				.color(conrod::color::BLACK)
+				.font_size(status_text_font_size)
+				.set(ids.text_game_status, ui);
+			control_buttons_down_from = ids.text_game_status;
+			control_buttons_padding = padding_vertical_bigger;
+		} else {
+			control_buttons_down_from = board_area_id;
+			control_buttons_padding = padding_vertical_biggest;
+		}
 
-				for _click in widget::Button::new()
-					.left_from(ids.button_solve, padding_vertical_smaller)
-					.w_h(game_button_width, game_button_height)
-					.label("Reset")
-					.label_font_size(game_button_font_size)
-					.set(ids.button_reset, ui) {
-						reset_game();
-					}
+		for _click in widget::Button::new()
+			.down_from(control_buttons_down_from, control_buttons_padding)
+			.align_middle_x()
+			.w_h(game_button_width, game_button_height)
+			.label(if is_solving { "Cancel" } else { "Solve it" })
+			.label_font_size(game_button_font_size)
+			.set(ids.button_solve, ui) {
+				// do not solve the board if it has already been solved or is hidden
+				if is_solving {
+					cancel_solving(state);
+				} else if !game_is_finished && !is_paused {
+					solve_game(state);
+				}
+			}
 
-				for _click in widget::Button::new()
-					.right_from(ids.button_solve, padding_vertical_smaller)
-					.w_h(game_button_width, game_button_height)
-					.label("Quit game")
-					.label_font_size(game_button_font_size)
-					.set(ids.button_quit_game, ui) {
-						quit_game();
-					}
-			} else {
-				let smaller_window_dimension = if ui.win_w < ui.win_h {
-					ui.win_w
-				} else {
-					ui.win_h
-				};
-				let mut title_button_height : f64;
-				let mut title_button_width : f64;
-				if ui.win_w < ui.win_h {
-					title_button_width = ui.win_w * 0.15;
-					title_button_height = title_button_width / 4.0;
-				} else {
-					title_button_height = ui.win_h * 0.036;
-					title_button_width = title_button_height * 4.0;
+		for _click in widget::Button::new()
+			.left_from(ids.button_solve, padding_vertical_smaller)
+			.w_h(game_button_width, game_button_height)
+			.label("Reset")
+			.label_font_size(game_button_font_size)
+			.set(ids.button_reset, ui) {
+				if !is_paused {
+					reset_game(state);
 				}
-				let title_font_size = ::std::cmp::min(120, (smaller_window_dimension * 0.25).round() as u32);	// the upper bound on font size prevents glium panicking with NoRoomForWholeQueue
-				let title_padding_top = 0.5 * ui.win_h - (title_font_size as f64);
-				let title_button_font_size = (title_button_height * 0.75).round() as u32;
-				let padding_title_buttons_larger = 0.011 * smaller_window_dimension;
-				let padding_title_buttons_smaller = padding_title_buttons_larger / 2.0;
-
-				widget::Text::new("Sudoku")
-					.down_from(ids.text_top_padding, title_padding_top)
-					.align_middle_x()
-					.color(conrod::color::BLACK)
-					.font_size(title_font_size)
-					.set(ids.text_title, ui);
+			}
 
-				// again, ugly
-				widget::Text::new("")
-					.down_from(ids.text_title, padding_vertical_biggest * 1.5)
-					.align_middle_x()
-					.font_size(title_button_font_size)
-					.set(ids.text_button_alignment, ui);
-
-				for _click in widget::Button::new()
-					.left_from(ids.button_medium, padding_title_buttons_larger)
-					.w_h(title_button_width, title_button_height)
-					.label("Easy")
-					.label_font_size(title_button_font_size)
-					.set(ids.button_easy, ui) {
-						start_new_game(Difficulty::Easy);
-					};
+		for _click in widget::Button::new()
+			.right_from(ids.button_solve, padding_vertical_smaller)
+			.w_h(game_button_width, game_button_height)
+			.label("Quit game")
+			.label_font_size(game_button_font_size)
+			.set(ids.button_quit_game, ui) {
+				quit_game(state);
+			}
 
-				for _click in widget::Button::new()
-					.left_from(ids.text_button_alignment, padding_title_buttons_smaller)
-					.w_h(title_button_width, title_button_height)
-					.label("Medium")
-					.label_font_size(title_button_font_size)
-					.set(ids.button_medium, ui) {
-						start_new_game(Difficulty::Medium);
-					};
+		// set after the board like Quit game, both end the game being drawn
+		for _click in widget::Button::new()
+			.left_from(ids.button_export, padding_vertical_smaller)
+			.w_h(game_button_width * 0.6, game_button_height * 0.8)
+			.label("Menu")
+			.label_font_size((game_button_font_size as f64 * 0.8).round() as u32)
+			.set(ids.button_menu, ui) {
+				return_to_menu(state);
+			}
 
-				for _click in widget::Button::new()
-					.right_from(ids.text_button_alignment, padding_title_buttons_smaller)
-					.w_h(title_button_width, title_button_height)
-					.label("Hard")
-					.label_font_size(title_button_font_size)
-					.set(ids.button_hard, ui) {
-						start_new_game(Difficulty::Hard);
-					};
+		// second row of buttons, centered like the difficulty buttons on the title screen
+		widget::Text::new("")
+			.down_from(ids.button_solve, padding_vertical_smaller)
+			.align_middle_x_of(ids.button_solve)
+			.font_size(game_button_font_size)
+			.set(ids.text_game_button_alignment, ui);
+
+		for _click in widget::Button::new()
+			.left_from(ids.text_game_button_alignment, padding_vertical_smaller / 2.0)
+			.w_h(game_button_width, game_button_height)
+			.label("Redo")
+			.label_font_size(game_button_font_size)
+			.set(ids.button_redo, ui) {
+				redo_move(state);
+			}
+
+		for _click in widget::Button::new()
+			.left_from(ids.button_redo, padding_vertical_smaller)
+			.w_h(game_button_width, game_button_height)
+			.label("Undo")
+			.label_font_size(game_button_font_size)
+			.set(ids.button_undo, ui) {
+				undo_move(state);
+			}
+
+		for _click in widget::Button::new()
+			.right_from(ids.text_game_button_alignment, padding_vertical_smaller / 2.0)
+			.w_h(game_button_width, game_button_height)
+			.label(if notes_mode { "Notes: on" } else { "Notes: off" })
+			.label_font_size(game_button_font_size)
+			.set(ids.button_notes, ui) {
+				toggle_notes_mode(state);
+			}
+
+		for _click in widget::Button::new()
+			.right_from(ids.button_notes, padding_vertical_smaller)
+			.w_h(game_button_width, game_button_height)
+			.label("Hint")
+			.label_font_size(game_button_font_size)
+			.set(ids.button_hint, ui) {
+				// hints make no sense for a finished board, one that is being solved or one that is hidden
+				if !game_is_finished && !is_solving && !is_paused {
+					show_hint(state);
+				}
+			}
+	} else {
+		let smaller_window_dimension = if ui.win_w < ui.win_h {
+			ui.win_w
+		} else {
+			ui.win_h
+		};
+		let mut title_button_height : f64;
+		let mut title_button_width : f64;
+		if ui.win_w < ui.win_h {
+			title_button_width = ui.win_w * 0.15;
+			title_button_height = title_button_width / 4.0;
+		} else {
+			title_button_height = ui.win_h * 0.036;
+			title_button_width = title_button_height * 4.0;
+		}
+		let title_font_size = ::std::cmp::min(120, (smaller_window_dimension * 0.25).round() as u32);	// the upper bound on font size prevents glium panicking with NoRoomForWholeQueue
+		let title_padding_top = 0.5 * ui.win_h - (title_font_size as f64);
+		let title_button_font_size = (title_button_height * 0.75).round() as u32;
+		let padding_title_buttons_larger = 0.011 * smaller_window_dimension;
+		let padding_title_buttons_smaller = padding_title_buttons_larger / 2.0;
+
+		if state.screen == Screen::Statistics {
+			widget::Text::new("Statistics")
+				.down_from(ids.text_top_padding, padding_vertical_biggest)
+				.align_middle_x()
+				.color(conrod::color::BLACK)
+				.font_size(title_button_font_size * 2)
+				.set(ids.text_statistics_title, ui);
 
-				for _click in widget::Button::new()
-					.right_from(ids.button_hard, padding_title_buttons_larger)
-					.w_h(title_button_width, title_button_height)
-					.label("Very Hard")
-					.label_font_size(title_button_font_size)
-					.set(ids.button_very_hard, ui) {
-						start_new_game(Difficulty::VeryHard);
+			widget::Text::new(statistics_text(&state.statistics).as_str())
+				.down_from(ids.text_statistics_title, padding_vertical_biggest)
+				.align_middle_x_of(ids.text_statistics_title)
+				.w(ui.win_w * 0.9)
+				.center_justify()
+				.color(conrod::color::BLACK)
+				.font_size(title_button_font_size)
+				.set(ids.text_statistics, ui);
+
+			for _click in widget::Button::new()
+				.down_from(ids.text_statistics, padding_vertical_biggest)
+				.align_middle_x_of(ids.text_statistics_title)
+				.w_h(title_button_width, title_button_height)
+				.label("Back")
+				.label_font_size(title_button_font_size)
+				.set(ids.button_statistics_back, ui) {
+					show_screen(state, Screen::Title);
+				};
+		} else if state.screen == Screen::LoadGame {
+			widget::Text::new("Saved games")
+				.down_from(ids.text_top_padding, padding_vertical_biggest)
+				.align_middle_x()
+				.color(conrod::color::BLACK)
+				.font_size(title_button_font_size * 2)
+				.set(ids.text_load_title, ui);
+
+			let slot_height = title_button_height * 1.5;
+			let mut slots_list_down_from : widget::id::Id;
+			if state.save_slots.is_empty() {
+				widget::Text::new("There are no saved games.")
+					.down_from(ids.text_load_title, padding_vertical_biggest)
+					.align_middle_x_of(ids.text_load_title)
+					.color(conrod::color::BLACK)
+					.font_size(title_button_font_size)
+					.set(ids.text_no_save_slots, ui);
+				slots_list_down_from = ids.text_no_save_slots;
+			} else {
+				// at most this many slots are shown at once, the list scrolls to show the rest
+				const MAX_VISIBLE_SLOTS : usize = 8;
+				let visible_slots = ::std::cmp::min(state.save_slots.len(), MAX_VISIBLE_SLOTS);
+				let (mut slot_items, _scrollbar) = widget::List::flow_down(state.save_slots.len())
+					.item_size(slot_height)
+					.scrollbar_next_to()
+					.down_from(ids.text_load_title, padding_vertical_biggest)
+					.align_middle_x_of(ids.text_load_title)
+					.w_h(ui.win_w * 0.8, slot_height * visible_slots as f64)
+					.set(ids.list_save_slots, ui);
+				while let Some(slot_item) = slot_items.next(ui) {
+					let index = slot_item.i;
+					let slot = &state.save_slots[index];
+					let slot_label = format!("{}   {}   {}% done   {}",
+						slot.saved_game.title,
+						slot.saved_game.difficulty.map_or("", |difficulty| difficulty.name()),
+						(slot.saved_game.progress() * 100.0).round(),
+						::format::format_time_ago(slot.last_played));
+					let slot_color = if state.selected_slot == Some(index) {
+						conrod::color::LIGHT_BLUE
+					} else {
+						conrod::color::WHITE
 					};
+					for _click in slot_item.set(widget::Button::new()
+						.label(slot_label.as_str())
+						.label_font_size(title_button_font_size)
+						.color(slot_color), ui) {
+							select_slot(state, index);
+						}
+				}
+				slots_list_down_from = ids.list_save_slots;
+			}
+
+			if let Some(ref load_error) = state.load_error {
+				widget::Text::new(load_error.as_str())
+					.down_from(slots_list_down_from, padding_vertical_biggest)
+					.align_middle_x_of(ids.text_load_title)
+					.color(conrod::color::DARK_RED)
+					.font_size(title_button_font_size)
+					.set(ids.text_load_error, ui);
+				slots_list_down_from = ids.text_load_error;
 			}
+
+			widget::Text::new("")
+				.down_from(slots_list_down_from, padding_vertical_biggest)
+				.align_middle_x_of(ids.text_load_title)
+				.font_size(title_button_font_size)
+				.set(ids.text_load_button_alignment, ui);
+
+			for _click in widget::Button::new()
+				.left_from(ids.text_load_button_alignment, padding_title_buttons_smaller)
+				.w_h(title_button_width, title_button_height)
+				.label("Load")
+				.label_font_size(title_button_font_size)
+				.set(ids.button_load_slot, ui) {
+					load_selected_slot(state);
+				};
+
+			for _click in widget::Button::new()
+				.right_from(ids.text_load_button_alignment, padding_title_buttons_smaller)
+				.w_h(title_button_width, title_button_height)
+				.label("Delete")
+				.label_font_size(title_button_font_size)
+				.set(ids.button_delete_slot, ui) {
+					delete_selected_slot(state);
+				};
+
+			for _click in widget::Button::new()
+				.down_from(ids.button_load_slot, padding_title_buttons_larger)
+				.align_middle_x_of(ids.text_load_button_alignment)
+				.w_h(title_button_width, title_button_height)
+				.label("Back")
+				.label_font_size(title_button_font_size)
+				.set(ids.button_load_back, ui) {
+					show_screen(state, Screen::Title);
+				};
 		} else {
-			panic!("gui::logic::draw_ui() called with STATE being None");
+			widget::Text::new("Sudoku")
+				.down_from(ids.text_top_padding, title_padding_top)
+				.align_middle_x()
+				.color(conrod::color::BLACK)
+				.font_size(title_font_size)
+				.set(ids.text_title, ui);
+
+			// again, ugly
+			widget::Text::new("")
+				.down_from(ids.text_title, padding_vertical_biggest * 1.5)
+				.align_middle_x()
+				.font_size(title_button_font_size)
+				.set(ids.text_button_alignment, ui);
+
+			for _click in widget::Button::new()
+				.left_from(ids.button_medium, padding_title_buttons_larger)
+				.w_h(title_button_width, title_button_height)
+				.label("Easy")
+				.label_font_size(title_button_font_size)
+				.set(ids.button_easy, ui) {
+					start_new_game(state, Difficulty::Easy);
+				};
+
+			for _click in widget::Button::new()
+				.left_from(ids.text_button_alignment, padding_title_buttons_smaller)
+				.w_h(title_button_width, title_button_height)
+				.label("Medium")
+				.label_font_size(title_button_font_size)
+				.set(ids.button_medium, ui) {
+					start_new_game(state, Difficulty::Medium);
+				};
+
+			for _click in widget::Button::new()
+				.right_from(ids.text_button_alignment, padding_title_buttons_smaller)
+				.w_h(title_button_width, title_button_height)
+				.label("Hard")
+				.label_font_size(title_button_font_size)
+				.set(ids.button_hard, ui) {
+					start_new_game(state, Difficulty::Hard);
+				};
+
+			for _click in widget::Button::new()
+				.right_from(ids.button_hard, padding_title_buttons_larger)
+				.w_h(title_button_width, title_button_height)
+				.label("Very Hard")
+				.label_font_size(title_button_font_size)
+				.set(ids.button_very_hard, ui) {
+					start_new_game(state, Difficulty::VeryHard);
+				};
+
+			let game_source_label = match state.game_source {
+				GameSource::Generator => "New games: generated",
+				GameSource::Database => "New games: predefined",
+				GameSource::Collection => "New games: imported"
+			};
+			for _click in widget::Button::new()
+				.down_from(ids.button_medium, padding_title_buttons_larger)
+				.align_middle_x_of(ids.text_button_alignment)
+				.w_h(2.0 * title_button_width + padding_title_buttons_smaller * 2.0, title_button_height)
+				.label(game_source_label)
+				.label_font_size(title_button_font_size)
+				.set(ids.button_game_source, ui) {
+					toggle_game_source(state);
+				};
+
+			for edit in widget::TextBox::new(state.seed_text.as_str())
+				.down_from(ids.button_game_source, padding_title_buttons_larger)
+				.align_middle_x_of(ids.text_button_alignment)
+				.w_h(2.0 * title_button_width + padding_title_buttons_smaller * 2.0, title_button_height)
+				.font_size(title_button_font_size)
+				.center_justify()
+				.set(ids.text_box_seed, ui) {
+					if let widget::text_box::Event::Update(text) = edit {
+						set_seed_text(state, text.as_str());
+					}
+				}
+
+			widget::Text::new("Seed")
+				.left_from(ids.text_box_seed, padding_title_buttons_larger)
+				.color(conrod::color::BLACK)
+				.font_size(title_button_font_size)
+				.set(ids.text_seed_label, ui);
+
+			for _click in widget::Button::new()
+				.down_from(ids.text_box_seed, padding_title_buttons_larger)
+				.align_middle_x_of(ids.text_button_alignment)
+				.w_h(2.0 * title_button_width + padding_title_buttons_smaller * 2.0, title_button_height)
+				.label("Continue / Load game")
+				.label_font_size(title_button_font_size)
+				.set(ids.button_load_game, ui) {
+					show_screen(state, Screen::LoadGame);
+				};
+
+			for _click in widget::Button::new()
+				.down_from(ids.button_load_game, padding_title_buttons_larger)
+				.align_middle_x_of(ids.text_button_alignment)
+				.w_h(2.0 * title_button_width + padding_title_buttons_smaller * 2.0, title_button_height)
+				.label("Statistics")
+				.label_font_size(title_button_font_size)
+				.set(ids.button_statistics, ui) {
+					show_screen(state, Screen::Statistics);
+				};
 		}
 	}
-}
\ No newline at end of file
+}
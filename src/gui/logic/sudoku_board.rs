@@ -12,7 +12,12 @@ pub struct SudokuBoard<'a> {
 	
 	board_original: Option<&'a game::SudokuBoard>,
 	board_playing: Option<&'a mut game::SudokuBoard>,
-	is_being_solved: Option<bool>
+	is_being_solved: Option<bool>,
+	notes: Option<&'a mut game::BoardNotes>,
+	notes_mode: bool,
+	highlighted_cell: Option<(usize, usize)>,
+	related_cells: &'a [(usize, usize)],
+	marked_cells: &'a [(usize, usize)]
 }
 
 // We use `#[derive(WidgetStyle)] to vastly simplify the definition and implementation of the
@@ -27,6 +32,7 @@ pub struct SudokuBoard<'a> {
 // not using the macro because I can't get lists to work by using the macro
 struct Ids {
     text_boxes: conrod::widget::id::List,
+	notes: conrod::widget::id::List,
 	borders_background: conrod::widget::id::Id
 }
 
@@ -34,9 +40,12 @@ impl Ids {
     pub fn new(mut generator: conrod::widget::id::Generator) -> Self {
         let mut ids = Ids {
             text_boxes: conrod::widget::id::List::new(),
+			notes: conrod::widget::id::List::new(),
 			borders_background: generator.next()
         };
         ids.text_boxes.resize(81, &mut generator);
+		// one for each digit of each cell
+		ids.notes.resize(81 * 9, &mut generator);
         ids
     }
 }
@@ -46,6 +55,14 @@ pub struct State {
     ids: Ids
 }
 
+/// What happened on the board during an update
+pub struct Event {
+	/// The board is completely and correctly filled in
+	pub is_finished: bool,
+	/// Changes the player has made to cells, in order
+	pub moves: Vec<game::Move>
+}
+
 impl<'a> SudokuBoard<'a> {
     /// Create a context to be built upon.
     pub fn new() -> Self {
@@ -53,7 +70,12 @@ impl<'a> SudokuBoard<'a> {
             common: widget::CommonBuilder::default(),
 			board_original: None,
 			board_playing: None,
-			is_being_solved: None
+			is_being_solved: None,
+			notes: None,
+			notes_mode: false,
+			highlighted_cell: None,
+			related_cells: &[],
+			marked_cells: &[]
         }
     }
 	
@@ -64,10 +86,30 @@ impl<'a> SudokuBoard<'a> {
 		self
 	}
 	
+	/// In notes mode typing a digit into an empty cell toggles that note instead of filling the cell in
+	pub fn set_notes(mut self : Self, notes : &'a mut game::BoardNotes, notes_mode : bool) -> Self {
+		self.notes = Some(notes);
+		self.notes_mode = notes_mode;
+		self
+	}
+	
+	/// Highlights a cell, such as the one a hint is about, along with the cells related to it
+	pub fn highlight_cells(mut self : Self, highlighted_cell : Option<(usize, usize)>, related_cells : &'a [(usize, usize)]) -> Self {
+		self.highlighted_cell = highlighted_cell;
+		self.related_cells = related_cells;
+		self
+	}
+	
+	/// Marks cells the player is told about, such as the ones that keep the puzzle from being solved
+	pub fn mark_cells(mut self : Self, marked_cells : &'a [(usize, usize)]) -> Self {
+		self.marked_cells = marked_cells;
+		self
+	}
+	
 	fn check_board(&self) -> (Option<Vec<game::SudokuBoardError>>, bool) {
 		let errors = game::check_for_errors(self.board_playing.as_ref().unwrap());
 		if errors.len() == 0 {
-			if game::find_unassigned_location(self.board_playing.as_ref().unwrap()).is_none() {
+			if self.board_playing.as_ref().unwrap().iter().all(|row| !row.contains(&0)) {
 				// board is solved
 				(None, true)
 			} else {
@@ -114,8 +156,8 @@ impl<'a> Widget for SudokuBoard<'a> {
     type State = State;
     /// The Style struct that we defined using the `widget_style!` macro.
     type Style = ();
-    /// The event produced by instantiating the widget - () if the game is finished, otherwise no event.
-    type Event = Option<()>;
+    /// The event produced by instantiating the widget - whether the game is finished, and the moves made.
+    type Event = Event;
 
     fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
         State {
@@ -146,6 +188,7 @@ impl<'a> Widget for SudokuBoard<'a> {
         let border_thin_width = 0.003 * rect.w();
         let text_box_dimension = (rect.w() - 4.0 * border_thick_width - 6.0 * border_thin_width) / 9.0;
 		let text_box_font_size = (0.65 * text_box_dimension).round() as u32;
+		let note_font_size = (0.22 * text_box_dimension).round() as u32;
 		
 		// draw borders
 		// borders are drawn as a background rect
@@ -158,6 +201,7 @@ impl<'a> Widget for SudokuBoard<'a> {
 		
 		// draw text boxes
 		let incorrect_cell_indeces = Self::get_incorrect_cell_indeces(&board_errors);
+		let mut moves : Vec<game::Move> = Vec::new();
 		let mut row_start_text_box_id : Option<conrod::widget::id::Id> = None;
 		let mut previous_text_box_id : Option<conrod::widget::id::Id> = None;
 		for (index, &text_box_id) in state.ids.text_boxes.iter().enumerate() {
@@ -175,8 +219,14 @@ impl<'a> Widget for SudokuBoard<'a> {
 			} else {
 				color::BLACK
 			};
-			let background_color = if incorrect_cell_indeces.contains(&(index as u8)) {
+			let background_color = if self.marked_cells.contains(&(board_i, board_j)) {
+				color::rgb(1.0, 0.6, 0.4)
+			} else if incorrect_cell_indeces.contains(&(index as u8)) {
 				color::rgb(1.0, 0.74902, 0.74902)
+			} else if self.highlighted_cell == Some((board_i, board_j)) {
+				color::rgb(1.0, 0.89804, 0.49804)
+			} else if self.related_cells.contains(&(board_i, board_j)) {
+				color::rgb(1.0, 0.96078, 0.81569)
 			} else {
 				color::WHITE
 			};
@@ -222,27 +272,70 @@ impl<'a> Widget for SudokuBoard<'a> {
 				if let widget::text_box::Event::Update(text) = edit {						
 					// prevent changing values when game solving has been requested and prevent changing of given cell values
 					if !self.is_being_solved.unwrap() && self.board_original.as_ref().unwrap()[board_i][board_j] == 0 {
+						let old_value = self.board_playing.as_ref().unwrap()[board_i][board_j];
+						let old_notes = self.notes.as_ref().unwrap().get(board_i, board_j);
+						// notes are only taken for empty cells
+						let is_taking_note = self.notes_mode && old_value == 0;
 						// find the last character in this text box, if there is one
 						if let Some(value) = text.chars().next_back() {
 							// convert the character to a number
 							if let Some(value) = value.to_digit(10) {
-								// set the field value to this number
-								self.board_playing.as_mut().unwrap()[board_i][board_j] = value as u8;
+								if is_taking_note {
+									// toggle the note for this number, zero clears all notes of the cell
+									if value == 0 {
+										self.notes.as_mut().unwrap().clear(board_i, board_j);
+									} else {
+										self.notes.as_mut().unwrap().toggle(board_i, board_j, value as u8);
+									}
+								} else {
+									// set the field value to this number
+									self.board_playing.as_mut().unwrap()[board_i][board_j] = value as u8;
+								}
 							}
 						} else {
 							// mark the field as empty
 							self.board_playing.as_mut().unwrap()[board_i][board_j] = 0;
 						}
+						
+						// report the change so that it can be undone
+						let new_value = self.board_playing.as_ref().unwrap()[board_i][board_j];
+						let new_notes = self.notes.as_ref().unwrap().get(board_i, board_j);
+						if new_value != old_value || new_notes != old_notes {
+							moves.push(game::Move{
+								row: board_i,
+								col: board_j,
+								old_value,
+								new_value,
+								old_notes,
+								new_notes
+							});
+						}
+					}
+				}
+			}
+
+			// draw the notes of an empty cell as a small 3x3 grid of digits on top of its text box
+			if text_box_value == 0 {
+				let note_offset = text_box_dimension / 3.0;
+				for value in 1..=9u8 {
+					if self.notes.as_ref().unwrap().contains(board_i, board_j, value) {
+						let note_row = ((value - 1) / 3) as f64;
+						let note_col = ((value - 1) % 3) as f64;
+						widget::Text::new(value.to_string().as_str())
+							.x_y_relative_to(text_box_id, (note_col - 1.0) * note_offset, (1.0 - note_row) * note_offset)
+							.font_size(note_font_size)
+							.color(color::rgb(0.4, 0.4, 0.4))
+							.graphics_for(text_box_id)
+							.set(state.ids.notes[index * 9 + (value - 1) as usize], ui);
 					}
 				}
 			}
 		}
 		
 		// return board status event
-		if board_done {
-			Some(())
-		} else {
-			None
+		Event {
+			is_finished: board_done,
+			moves
 		}
     }
 }
\ No newline at end of file
@@ -3,16 +3,14 @@ mod logic;
 
 pub use self::logic::initialize;
 
-/// GUI initialization and event loop
-pub fn start() {
+/// GUI initialization and event loop, the state is owned by the loop until the window is closed
+pub fn start(mut state : logic::State) {
 	use self::logic;
 	use conrod::{
 		self,
 		backend::glium::glium::{self, Surface}
 	};
 
-	logic::check_initialized("gui::start() called when gui::initialize() has not been called before");
-	
 	// resulting window is resizable, this is preferred size
 	const WINDOW_WIDTH: u32 = 560;
 	const WINDOW_HEIGHT: u32 = 570;
@@ -33,7 +31,8 @@ pub fn start() {
 	let ids = logic::generate_widget_ids(&mut ui);
 
 	// Add a `Font` to the `Ui`'s `font::Map` from file.
-	ui.fonts.insert_from_file("./resources/fonts/NotoSans/NotoSans-Regular.ttf").expect("can not load font resource");
+	let font_path = ::game::find_resource("fonts/NotoSans/NotoSans-Regular.ttf").expect("can not find font resource");
+	ui.fonts.insert_from_file(font_path).expect("can not load font resource");
 
 	// A type used for converting `conrod::render::Primitives` into `Command`s that can be used for drawing to the glium `Surface`.
 	let mut renderer = conrod::backend::glium::Renderer::new(&display).unwrap();
@@ -49,12 +48,18 @@ pub fn start() {
 		// Get all the new events since the last frame.
 		events_loop.poll_events(|event| { events.push(event); });
 
-		// If there are no new events, wait for one.
+		// If there are no new events, wait for one, unless the game timer or the solver is running and has to be kept up to date.
 		if events.is_empty() {
-			events_loop.run_forever(|event| {
-				events.push(event);
-				glium::glutin::ControlFlow::Break
-			});
+			if logic::needs_redrawing(&state) {
+				::std::thread::sleep(::std::time::Duration::from_millis(100));
+				let ui = &mut ui.set_widgets();
+				logic::draw_ui(ui, &ids, &mut state);
+			} else {
+				events_loop.run_forever(|event| {
+					events.push(event);
+					glium::glutin::ControlFlow::Break
+				});
+			}
 		}
 
 		// Process the events.
@@ -71,9 +76,28 @@ pub fn start() {
 							},
 							..
 						} => {
-							logic::on_exit_event();
+							logic::on_exit_event(&mut state);
 							break 'render
 						},
+						// Ctrl+Z and Ctrl+Y undo and redo the player's moves
+						glium::glutin::WindowEvent::KeyboardInput {
+							input: glium::glutin::KeyboardInput {
+								state: glium::glutin::ElementState::Pressed,
+								virtual_keycode: Some(glium::glutin::VirtualKeyCode::Z),
+								modifiers: glium::glutin::ModifiersState { ctrl: true, .. },
+								..
+							},
+							..
+						} => logic::undo_move(&mut state),
+						glium::glutin::WindowEvent::KeyboardInput {
+							input: glium::glutin::KeyboardInput {
+								state: glium::glutin::ElementState::Pressed,
+								virtual_keycode: Some(glium::glutin::VirtualKeyCode::Y),
+								modifiers: glium::glutin::ModifiersState { ctrl: true, .. },
+								..
+							},
+							..
+						} => logic::redo_move(&mut state),
 						_ => (),
 					}
 				}
@@ -92,7 +116,7 @@ pub fn start() {
 			// Set the widgets.
 			let ui = &mut ui.set_widgets();
 			
-			logic::draw_ui(ui, &ids);
+			logic::draw_ui(ui, &ids, &mut state);
 		}
 
 		// Draw the `Ui` if it has changed.
@@ -0,0 +1,7 @@
+//! The Sudoku game engine: boards and their error checking, the solvers and puzzle rating, new games from the generator,
+//! the predefined database and the user collection, saved games, statistics and settings, and importing and exporting puzzles.
+//! The `sudoku` binary, with its game window, terminal frontend and command line, is built on top of it.
+
+// Declare the modules
+pub mod game;
+mod util;
@@ -1,17 +1,96 @@
+#[cfg(feature = "gui")]
 #[macro_use]
 extern crate conrod;
+#[cfg(feature = "gui")]
 #[macro_use]
 extern crate conrod_derive;
+extern crate sudoku;
 
-mod game;
+// the game engine comes from the library crate, the frontends are part of the binary
+use sudoku::game;
+
+mod cli;
+mod format;
+#[cfg(feature = "gui")]
 mod gui;
-mod util;
+mod tui;
 
 fn main() {
-    // read in files for new game generation
-    game::initialize_database();
+    // directory flags come before the command, the directories given there take precedence over the environment and the platform defaults
+    let args : Vec<String> = std::env::args().skip(1).collect();
+    let mut index = 0;
+    let mut import_paths : Vec<String> = Vec::new();
+    while index < args.len() && args[index].starts_with("--") {
+        let flag = args[index].as_str();
+        if flag == "--help" {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        if flag != "--data-dir" && flag != "--config-dir" && flag != "--import" {
+            eprintln!("unknown argument {}\n\n{}", flag, cli::USAGE);
+            std::process::exit(2);
+        }
+        let value = match args.get(index + 1) {
+            Some(value) => value.clone(),
+            None => {
+                eprintln!("{} requires a {}", flag, if flag == "--import" { "file" } else { "directory" });
+                std::process::exit(2);
+            }
+        };
+        let is_set = if flag == "--data-dir" {
+            game::set_data_dir(value.into()).is_ok()
+        } else if flag == "--config-dir" {
+            game::set_config_dir(value.into()).is_ok()
+        } else {
+            // --import <file> is kept working as it was before the import command
+            import_paths.push(value);
+            true
+        };
+        if !is_set {
+            eprintln!("{} may only be given once", flag);
+            std::process::exit(2);
+        }
+        index += 2;
+    }
+
+    // the game saved by older versions is moved into a save slot once, before a frontend lists the slots
+    if index == args.len() || args[index] == "tui" {
+        game::migrate_legacy_saved_game();
+    }
+
+    // commands do their work without starting the GUI
+    if index < args.len() {
+        std::process::exit(cli::run(args[index].as_str(), &args[index + 1..]));
+    }
+    if !import_paths.is_empty() {
+        std::process::exit(cli::run("import", &import_paths));
+    }
+
+    start_frontend();
+}
+
+#[cfg(feature = "gui")]
+fn start_frontend() {
+    // read in files for new game generation, the game can not start without them
+    let database = match game::Database::load() {
+        Ok((database, skipped_lines)) => {
+            for skipped_line in skipped_lines.iter() {
+                eprintln!("{}", skipped_line);
+            }
+            database
+        },
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
 
     // initialize GUI and game state, then start the event loop
-    gui::initialize();
-    gui::start();
-}
\ No newline at end of file
+    gui::start(gui::initialize(database));
+}
+
+/// Built without the game window, the game is played in the terminal
+#[cfg(not(feature = "gui"))]
+fn start_frontend() {
+    std::process::exit(cli::run("tui", &[]));
+}
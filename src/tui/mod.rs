@@ -0,0 +1,412 @@
+use ::game::{self, SudokuBoard, Difficulty, GameSource, GameSession, Database, Statistics};
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+
+/// Shown under the board, the keys can be combined on a line, such as "lll5" to move three cells right and enter a 5
+const GAME_HELP : &str = "Keys: h j k l or a s d w move, 1-9 enter a digit, 0 or x clears; keys take effect on Enter and a line may hold several.
+Commands: undo, redo, notes, reset, solve, menu (the game stays saved), quit (gives the game up), help.";
+
+/// A game played in the terminal, its session keeps it in a save slot just like the games of the GUI
+struct Game {
+    session: GameSession,
+    cursor: (usize, usize),
+    message: Option<String>
+}
+
+impl Game {
+    fn new(session : GameSession) -> Game {
+        Game{
+            session,
+            cursor: (0, 0),
+            message: None
+        }
+    }
+
+    /// Handles a line of keys: moving the cursor, entering digits and clearing cells
+    /// Returns whether the board or the notes have changed
+    fn handle_keys(&mut self, keys : &str) -> bool {
+        let mut changed = false;
+        for key in keys.chars() {
+            let (i, j) = self.cursor;
+            match key {
+                'h' | 'a' => self.cursor.1 = (j + 8) % 9,
+                'l' | 'd' => self.cursor.1 = (j + 1) % 9,
+                'k' | 'w' => self.cursor.0 = (i + 8) % 9,
+                'j' | 's' => self.cursor.0 = (i + 1) % 9,
+                '0' ..= '9' | 'x' | '.' => changed |= self.enter(key.to_digit(10).unwrap_or(0) as u8),
+                _ if key.is_whitespace() => (),
+                _ => {
+                    self.message = Some(format!("Unknown key '{}', type help for the keys and commands.", key));
+                    break;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Enters a value into the cell under the cursor, or toggles it as a note of an empty cell in notes mode; zero clears the cell or its notes
+    /// Given cells can not be changed
+    fn enter(&mut self, value : u8) -> bool {
+        let (i, j) = self.cursor;
+        let session = &mut self.session;
+        if session.board_original[i][j] != 0 || !session.is_timer_running() {
+            return false;
+        }
+        let old_value = session.board_playing[i][j];
+        let old_notes = session.notes.get(i, j);
+        // notes are only taken for empty cells
+        if session.notes_mode && old_value == 0 {
+            if value == 0 {
+                session.notes.clear(i, j);
+            } else {
+                session.notes.toggle(i, j, value);
+            }
+        } else {
+            session.board_playing[i][j] = value;
+        }
+        let new_value = session.board_playing[i][j];
+        let new_notes = session.notes.get(i, j);
+        if new_value == old_value && new_notes == old_notes {
+            return false;
+        }
+        session.push_move(game::Move{
+            row: i,
+            col: j,
+            old_value,
+            new_value,
+            old_notes,
+            new_notes
+        });
+        true
+    }
+}
+
+/// Whether the terminal should be sent colour escape sequences, see https://no-color.org
+fn use_colors() -> bool {
+    ::std::env::var_os("NO_COLOR").is_none()
+}
+
+fn paint(text : &str, codes : &[&str], colors : bool) -> String {
+    if colors && !codes.is_empty() {
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+    } else {
+        String::from(text)
+    }
+}
+
+/// The cells in the rows, columns and boxes that hold the same value twice
+fn incorrect_cells(board : &SudokuBoard) -> HashSet<(usize, usize)> {
+    let mut cells : HashSet<(usize, usize)> = HashSet::new();
+    for error in game::check_for_errors(board).iter() {
+        match *error {
+            game::SudokuBoardError::Row(i) => cells.extend((0..9).map(|j| (i as usize, j))),
+            game::SudokuBoardError::Column(j) => cells.extend((0..9).map(|i| (i, j as usize))),
+            game::SudokuBoardError::Quadrant(qi, qj) => {
+                for i in (3 * qi as usize)..(3 * qi as usize + 3) {
+                    cells.extend((3 * qj as usize..3 * qj as usize + 3).map(|j| (i, j)));
+                }
+            }
+        }
+    }
+    cells
+}
+
+/// Draws the board with box-drawing characters, heavy lines around the boxes and light ones between the cells
+/// The cell under the cursor is put in brackets and marked cells between asterisks; with colors, givens are bold, entries blue,
+/// cells in conflicting rows, columns and boxes red and marked cells yellow
+fn draw_board(board_original : &SudokuBoard, board_playing : &SudokuBoard, cursor : Option<(usize, usize)>, marked : &[(usize, usize)], colors : bool) -> String {
+    let border = |left : char, fill : char, thin : char, thick : char, right : char| -> String {
+        let mut line = String::new();
+        line.push(left);
+        for j in 0..9 {
+            line.extend([fill, fill, fill].iter());
+            line.push(if j == 8 { right } else if j % 3 == 2 { thick } else { thin });
+        }
+        line.push('\n');
+        line
+    };
+    let incorrect = incorrect_cells(board_playing);
+    let mut result = border('┏', '━', '┯', '┳', '┓');
+    for i in 0..9 {
+        result.push('┃');
+        for j in 0..9 {
+            let value = board_playing[i][j];
+            let digit = if value == 0 { String::from(" ") } else { value.to_string() };
+            let cell = if cursor == Some((i, j)) {
+                format!("[{}]", digit)
+            } else if marked.contains(&(i, j)) && !colors {
+                format!("*{}*", digit)
+            } else {
+                format!(" {} ", digit)
+            };
+            let mut codes : Vec<&str> = Vec::new();
+            if board_original[i][j] != 0 {
+                codes.push("1");
+            } else if value != 0 {
+                codes.push("34");
+            }
+            if marked.contains(&(i, j)) {
+                codes.push("43");
+            } else if incorrect.contains(&(i, j)) {
+                codes.push("41");
+            }
+            result.push_str(paint(cell.as_str(), &codes, colors).as_str());
+            result.push(if j % 3 == 2 { '┃' } else { '│' });
+        }
+        result.push('\n');
+        result.push_str(match i {
+            8 => border('┗', '━', '┷', '┻', '┛'),
+            2 | 5 => border('┣', '━', '┿', '╋', '┫'),
+            _ => border('┠', '─', '┼', '╂', '┨')
+        }.as_str());
+    }
+    result
+}
+
+fn clear_screen() {
+    if use_colors() {
+        print!("\x1b[2J\x1b[H");
+    }
+}
+
+/// Reads a line of input, None once standard input has ended
+/// The terminal is left in its line mode, so keys only arrive once the player presses Enter
+fn read_line<R : BufRead>(input : &mut R) -> Option<String> {
+    print!("> ");
+    let _ = io::stdout().flush();
+    let mut line = String::new();
+    match input.read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(String::from(line.trim()))
+    }
+}
+
+/// Runs the terminal frontend until the player quits or standard input ends
+pub fn run() {
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut statistics = game::load_statistics();
+    let mut game_source = game::load_settings().game_source;
+    // the predefined games are read the first time they are asked for
+    let mut database : Option<Database> = None;
+    let mut message : Option<String> = None;
+    loop {
+        clear_screen();
+        println!("Sudoku\n");
+        println!("  1  Easy        2  Medium      3  Hard        4  Very hard");
+        println!("     (a seed may follow the number, such as \"3 12345\", to replay a game)");
+        println!("  n  New games: {}", match game_source {
+            GameSource::Generator => "generated",
+            GameSource::Database => "predefined",
+            GameSource::Collection => "imported"
+        });
+        println!("  l  Continue / Load game");
+        println!("  q  Quit\n");
+        if let Some(message) = message.take() {
+            println!("{}\n", message);
+        }
+        let line = match read_line(&mut input) {
+            Some(line) => line,
+            None => return
+        };
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some(choice @ "1") | Some(choice @ "2") | Some(choice @ "3") | Some(choice @ "4") => {
+                let difficulty = Difficulty::ALL[choice.parse::<usize>().unwrap() - 1];
+                let seed = match words.next().map(|seed| seed.parse::<u64>()) {
+                    Some(Ok(seed)) => seed,
+                    Some(Err(_)) => {
+                        message = Some(String::from("The seed has to be a number."));
+                        continue;
+                    },
+                    None => game::random_seed()
+                };
+                // the lines of the files that are not valid games are shown under the first game's board
+                let mut skipped_message = None;
+                if game_source == GameSource::Database && database.is_none() {
+                    match Database::load() {
+                        Ok((loaded, skipped_lines)) => {
+                            if !skipped_lines.is_empty() {
+                                skipped_message = Some(skipped_lines.iter().map(|skipped_line| skipped_line.to_string()).collect::<Vec<String>>().join("\n"));
+                            }
+                            database = Some(loaded);
+                        },
+                        Err(error) => {
+                            message = Some(format!("The predefined games can not be read: {}.", error));
+                            continue;
+                        }
+                    }
+                }
+                statistics.record_started(difficulty);
+                game::save_statistics(&statistics);
+                let session = GameSession::new(difficulty, game_source, seed, database.as_ref()).expect("the database is loaded for predefined games");
+                let mut game = Game::new(session);
+                game.message = save_error(&mut game.session).or(skipped_message);
+                if !play(game, &mut input, &mut statistics) {
+                    return;
+                }
+            },
+            Some("n") => {
+                game_source = match game_source {
+                    GameSource::Generator => GameSource::Database,
+                    GameSource::Database => GameSource::Collection,
+                    GameSource::Collection => GameSource::Generator
+                };
+                game::save_settings(&game::Settings{
+                    game_source
+                });
+            },
+            Some("l") => match load_game(&mut input) {
+                Some(Some(game)) => if !play(game, &mut input, &mut statistics) {
+                    return;
+                },
+                Some(None) => (),
+                None => return
+            },
+            Some("q") => return,
+            _ => message = Some(String::from("Type one of the numbers or letters above."))
+        }
+    }
+}
+
+/// Lists the saved games for the player to pick one, returns None once standard input has ended and Some(None) if the player goes back
+fn load_game<R : BufRead>(input : &mut R) -> Option<Option<Game>> {
+    let mut message : Option<String> = None;
+    loop {
+        let slots = game::list_save_slots();
+        clear_screen();
+        println!("Continue / Load game\n");
+        if slots.is_empty() {
+            println!("  There are no saved games.");
+        }
+        for (index, slot) in slots.iter().enumerate() {
+            let saved_game = &slot.saved_game;
+            let difficulty = saved_game.difficulty.map_or("Unknown difficulty", |difficulty| difficulty.name());
+            println!("  {:>2}  {}\n      {}, {}% done, {}", index + 1, saved_game.title, difficulty,
+                (saved_game.progress() * 100.0).round(), ::format::format_time_ago(slot.last_played));
+        }
+        println!("\nType a number to load that game, d and a number to delete it, b to go back.\n");
+        if let Some(message) = message.take() {
+            println!("{}\n", message);
+        }
+        let line = read_line(input)?;
+        let words : Vec<&str> = line.split_whitespace().collect();
+        let (is_delete, number) = match words.as_slice() {
+            ["b"] => return Some(None),
+            ["d", number] => (true, *number),
+            [number] => (false, *number),
+            _ => (false, "")
+        };
+        let index = match number.parse::<usize>() {
+            Ok(number) if number >= 1 && number <= slots.len() => number - 1,
+            _ => {
+                message = Some(String::from("There is no saved game with that number."));
+                continue;
+            }
+        };
+        if is_delete {
+            game::delete_save_slot(slots[index].name.as_str());
+            continue;
+        }
+        // the game is read again in case the slot has changed since it was listed
+        match game::load_from_slot(slots[index].name.as_str()) {
+            Ok(saved_game) => return Some(Some(Game::new(GameSession::from_saved_game(saved_game, slots[index].name.clone())))),
+            Err(error) => message = Some(format!("The game can not be loaded: {}.", error))
+        }
+    }
+}
+
+/// Plays a game until the player leaves it, returns false once standard input has ended
+fn play<R : BufRead>(mut game : Game, input : &mut R, statistics : &mut Statistics) -> bool {
+    let colors = use_colors();
+    let mut show_help = false;
+    loop {
+        clear_screen();
+        println!("{}   {}\n", game.session.title, ::format::format_duration(game.session.elapsed_time()));
+        let is_playing = game.session.is_timer_running();
+        let cursor = if is_playing { Some(game.cursor) } else { None };
+        let marked = game.session.solver_feedback().map_or(&[][..], |feedback| feedback.cells());
+        print!("{}", draw_board(&game.session.board_original, &game.session.board_playing, cursor, marked, colors));
+        let (i, j) = game.cursor;
+        let notes : Vec<String> = (1..10).filter(|value| game.session.notes.contains(i, j, *value)).map(|value| value.to_string()).collect();
+        println!("Row {}, column {}   Notes: {}{}", i + 1, j + 1, if game.session.notes_mode { "on" } else { "off" },
+            if notes.is_empty() { String::new() } else { format!(", this cell: {}", notes.join(" ")) });
+        if game.session.is_won() {
+            println!("You won. Congratulations! Type menu to go back.");
+        } else if !is_playing {
+            // the solver has filled the board in, what it has found out stays shown until the player leaves
+            let feedback = game.session.solver_feedback().map_or("The solution has been filled in.", |feedback| feedback.message());
+            println!("{} Type menu to go back.", feedback);
+        }
+        if let Some(message) = game.message.take() {
+            println!("{}", message);
+        }
+        if show_help {
+            println!("\n{}", GAME_HELP);
+            show_help = false;
+        } else {
+            println!("Type help for the keys and commands.");
+        }
+
+        let line = match read_line(input) {
+            Some(line) => line,
+            None => {
+                if let Some(message) = save_error(&mut game.session) {
+                    eprintln!("{}", message);
+                }
+                return false;
+            }
+        };
+        match line.as_str() {
+            "help" => show_help = true,
+            "undo" => if is_playing {
+                game.session.undo();
+            },
+            "redo" => if is_playing {
+                game.session.redo();
+            },
+            "notes" => game.session.toggle_notes_mode(),
+            "reset" => if is_playing {
+                game.session.reset();
+            },
+            "solve" => if is_playing {
+                // there is nothing else to do while the solver runs, it gives up by itself if it takes too long
+                println!("Solving…");
+                game.session.start_solving();
+                game.session.wait_for_solver(statistics);
+                // once a solution is filled in, the feedback is shown in place of the won message
+                if !game.session.is_finished() {
+                    game.message = game.session.solver_feedback().map(|feedback| String::from(feedback.message()));
+                }
+            },
+            "menu" => {
+                if let Some(message) = save_error(&mut game.session) {
+                    eprintln!("{}", message);
+                }
+                return true;
+            },
+            "quit" => {
+                game.session.abandon(statistics);
+                return true;
+            },
+            keys => {
+                game.handle_keys(keys);
+            }
+        }
+        game.session.update(statistics);
+        if game.session.needs_saving() {
+            if let Some(message) = save_error(&mut game.session) {
+                game.message = Some(message);
+            }
+        }
+    }
+}
+
+/// Saves the game, returns what to tell the player if that fails
+fn save_error(session : &mut GameSession) -> Option<String> {
+    session.save().err().map(|error| format!("The game can not be saved: {}.", error))
+}
+
+// Declares the test module
+#[cfg(test)]
+mod tests;
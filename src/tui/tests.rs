@@ -0,0 +1,49 @@
+use super::{Game, draw_board};
+use ::game::{SudokuBoard, SavedGame, GameSession, BoardNotes, MoveHistory};
+use std::time::Duration;
+
+fn test_game() -> Game {
+    let board = SudokuBoard::new("530070000600195000098000060800060003400803001700020006060000280000419005000080079");
+    Game::new(GameSession::from_saved_game(SavedGame{
+        board_original: board,
+        board_playing: board,
+        title: String::from("Easy game #1, seed 1"),
+        notes: BoardNotes::new(),
+        history: MoveHistory::new(),
+        elapsed: Duration::from_secs(0),
+        difficulty: None,
+        seed: None,
+        notes_mode: false
+    }, String::from("Easy_game__1__seed_1")))
+}
+
+#[test]
+fn draw_board_box_drawing() {
+    let game = test_game();
+    let drawn = draw_board(&game.session.board_original, &game.session.board_playing, Some((0, 2)), &[(0, 3)], false);
+    let lines : Vec<&str> = drawn.lines().collect();
+    assert_eq!(lines.len(), 19, "tui::draw_board() does not draw a line per row and per border");
+    assert_eq!(lines[0], "┏━━━┯━━━┯━━━┳━━━┯━━━┯━━━┳━━━┯━━━┯━━━┓", "tui::draw_board() does not draw the top border");
+    assert_eq!(lines[1], "┃ 5 │ 3 │[ ]┃* *│ 7 │   ┃   │   │   ┃", "tui::draw_board() does not draw the cells, the cursor and the marked cells");
+    assert_eq!(lines[6], "┣━━━┿━━━┿━━━╋━━━┿━━━┿━━━╋━━━┿━━━┿━━━┫", "tui::draw_board() does not draw heavy lines between the boxes");
+    assert!(!drawn.contains('\x1b'), "tui::draw_board() uses colors when asked not to");
+}
+
+#[test]
+fn handle_keys_moves_and_enters() {
+    let mut game = test_game();
+    assert!(game.handle_keys("ll4"), "tui::Game::handle_keys() does not report entering a digit");
+    assert_eq!(game.cursor, (0, 2), "tui::Game::handle_keys() does not move the cursor");
+    assert_eq!(game.session.board_playing[0][2], 4, "tui::Game::handle_keys() does not enter the digit under the cursor");
+    assert!(!game.handle_keys("h9"), "tui::Game::handle_keys() changes a given cell");
+    assert_eq!(game.session.board_playing[0][1], 3, "tui::Game::handle_keys() changes a given cell");
+    game.handle_keys("khh");
+    assert_eq!(game.cursor, (8, 8), "tui::Game::handle_keys() does not wrap the cursor around the board");
+    game.handle_keys("jldx");
+    assert_eq!(game.cursor, (0, 1), "tui::Game::handle_keys() does not move the cursor with w a s d");
+    assert!(game.session.undo(), "tui::Game::handle_keys() does not keep moves in the history");
+    assert_eq!(game.session.board_playing[0][2], 0, "tui::Game::handle_keys() keeps moves that can not be undone");
+    assert!(!game.session.history.can_undo(), "tui::Game::handle_keys() records changes that did not happen");
+    assert!(!game.handle_keys("q"), "tui::Game::handle_keys() accepts unknown keys");
+    assert!(game.message.is_some(), "tui::Game::handle_keys() does not explain unknown keys");
+}
@@ -1,8 +1,12 @@
-use std::path::PathBuf;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 pub fn is_numeric(val : &str) -> bool {
     for c in val.chars() {
-        if !c.is_digit(10) {
+        if !c.is_ascii_digit() {
             return false;
         }
     }
@@ -14,4 +18,136 @@ pub fn exe_dir() -> PathBuf {
     let mut path = current_exe().expect("can not get EXE directory");
     path.pop();   // strips EXE name path component
     path
-}
\ No newline at end of file
+}
+
+/// Name of the subdirectory the game keeps its files in, within the per-user data and config directories
+const APPLICATION_DIR_NAME : &str = "sudoku";
+
+// set from command line flags, these take precedence over the environment
+static DATA_DIR_OVERRIDE : OnceLock<PathBuf> = OnceLock::new();
+static CONFIG_DIR_OVERRIDE : OnceLock<PathBuf> = OnceLock::new();
+
+/// Makes data_dir() return the given directory, used for the --data-dir command line flag
+/// It can only be set once, a second directory is handed back as the error
+pub fn set_data_dir(path : PathBuf) -> Result<(), PathBuf> {
+    DATA_DIR_OVERRIDE.set(path)
+}
+
+/// Makes config_dir() return the given directory, used for the --config-dir command line flag
+/// It can only be set once, a second directory is handed back as the error
+pub fn set_config_dir(path : PathBuf) -> Result<(), PathBuf> {
+    CONFIG_DIR_OVERRIDE.set(path)
+}
+
+/// Directory for saved games and statistics
+/// It is the --data-dir flag, the SUDOKU_DATA_DIR environment variable, or the platform's per-user data directory:
+/// $XDG_DATA_HOME/sudoku or ~/.local/share/sudoku, and %APPDATA%\sudoku on Windows
+/// The directory may not exist yet, it is created when the first file is written to it
+pub fn data_dir() -> PathBuf {
+    DATA_DIR_OVERRIDE.get().cloned()
+        .or_else(|| env_dir("SUDOKU_DATA_DIR"))
+        .unwrap_or_else(|| user_dir("XDG_DATA_HOME", &[".local", "share"]))
+}
+
+/// Directory for settings
+/// It is the --config-dir flag, the SUDOKU_CONFIG_DIR environment variable, or the platform's per-user config directory:
+/// $XDG_CONFIG_HOME/sudoku or ~/.config/sudoku, and %APPDATA%\sudoku on Windows
+/// The directory may not exist yet, it is created when the first file is written to it
+pub fn config_dir() -> PathBuf {
+    CONFIG_DIR_OVERRIDE.get().cloned()
+        .or_else(|| env_dir("SUDOKU_CONFIG_DIR"))
+        .unwrap_or_else(|| user_dir("XDG_CONFIG_HOME", &[".config"]))
+}
+
+fn env_dir(variable : &str) -> Option<PathBuf> {
+    env::var_os(variable).filter(|value| !value.is_empty()).map(PathBuf::from)
+}
+
+/// The per-user directory given by the XDG variable, or the given path within the home directory,
+/// falling back to the executable's directory when there is no home directory
+#[cfg(not(windows))]
+fn user_dir(xdg_variable : &str, home_relative_path : &[&str]) -> PathBuf {
+    let mut dir = match env_dir(xdg_variable) {
+        Some(dir) => dir,
+        None => match env_dir("HOME") {
+            Some(mut dir) => {
+                for component in home_relative_path {
+                    dir.push(component);
+                }
+                dir
+            },
+            None => return exe_dir()
+        }
+    };
+    dir.push(APPLICATION_DIR_NAME);
+    dir
+}
+
+#[cfg(windows)]
+fn user_dir(_xdg_variable : &str, _home_relative_path : &[&str]) -> PathBuf {
+    match env_dir("APPDATA") {
+        Some(mut dir) => {
+            dir.push(APPLICATION_DIR_NAME);
+            dir
+        },
+        None => exe_dir()
+    }
+}
+
+/// Finds a file among the game's resources, such as "games/easy.sud"
+/// The resources directory is looked for in the SUDOKU_RESOURCES_DIR environment variable, next to the executable,
+/// in share/sudoku of the install prefix the executable is in, in the source checkout the game was built from and in the current directory
+pub fn find_resource(relative_path : &str) -> Option<PathBuf> {
+    resource_dirs().into_iter()
+        .map(|mut path| {
+            path.push(relative_path);
+            path
+        })
+        .find(|path| path.is_file())
+}
+
+fn resource_dirs() -> Vec<PathBuf> {
+    let mut dirs : Vec<PathBuf> = Vec::new();
+    if let Some(dir) = env_dir("SUDOKU_RESOURCES_DIR") {
+        dirs.push(dir);
+    }
+    let mut exe_resources = exe_dir();
+    exe_resources.push("resources");
+    dirs.push(exe_resources);
+    // <prefix>/bin/sudoku has its resources in <prefix>/share/sudoku/resources
+    let mut prefix_resources = exe_dir();
+    if prefix_resources.pop() {
+        prefix_resources.push("share");
+        prefix_resources.push(APPLICATION_DIR_NAME);
+        prefix_resources.push("resources");
+        dirs.push(prefix_resources);
+    }
+    let mut checkout_resources = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    checkout_resources.push("resources");
+    dirs.push(checkout_resources);
+    dirs.push(PathBuf::from("resources"));
+    dirs
+}
+
+/// Writes a file by writing a temporary file next to it and renaming that over the original,
+/// so that an interrupted write never leaves a partially written file behind
+/// The directory the file is in is created if it does not exist
+pub fn write_file_atomically(path : &Path, contents : &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    let result = File::create(&temp_path)
+        .and_then(|mut file| file.write_all(contents).and_then(|_| file.sync_all()))
+        .and_then(|_| fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+// Declares the test module
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,27 @@
+use super::write_file_atomically;
+use std::env::temp_dir;
+use std::fs;
+
+#[test]
+fn write_file_atomically_replaces_file() {
+    let mut path = temp_dir();
+    path.push(format!("sudoku_write_file_atomically_{}.txt", ::std::process::id()));
+    write_file_atomically(path.as_path(), b"first").expect("util::write_file_atomically() fails to create a file");
+    write_file_atomically(path.as_path(), b"second").expect("util::write_file_atomically() fails to replace a file");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "second", "util::write_file_atomically() does not replace the file contents");
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    assert!(fs::metadata(&temp_path).is_err(), "util::write_file_atomically() leaves the temporary file behind");
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn write_file_atomically_creates_directory() {
+    let mut dir = temp_dir();
+    dir.push(format!("sudoku_write_file_atomically_dir_{}", ::std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("saves").join("game.sud");
+    write_file_atomically(path.as_path(), b"game").expect("util::write_file_atomically() does not create the directory of the file");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "game", "util::write_file_atomically() does not write the file into a new directory");
+    let _ = fs::remove_dir_all(&dir);
+}